@@ -0,0 +1,173 @@
+use crate::*;
+
+//the tokens held back by the team for giveaways
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Reserve {
+    pub max: u32,    // maximum number of tokens that can be airdropped
+    pub minted: u32, // number of tokens airdropped so far
+}
+
+#[near_bindgen]
+impl Contract {
+    /*
+        set how many tokens can be airdropped in total. It can't go below what was already airdropped, nor hold back
+        more tokens than are left. The unused part of the reserve can't be bought.
+    */
+    pub fn set_reserve_size(&mut self, max: u32) {
+        self.assert_role(Role::Admin);
+        assert!(
+            max >= self.reserve.minted,
+            "{} tokens were already airdropped",
+            self.reserve.minted
+        );
+        let available = self.internal_available_count();
+        assert!(
+            max - self.reserve.minted <= available,
+            "Exceeds the {} tokens left",
+            available
+        );
+        self.reserve.max = max;
+    }
+
+    //get the size of the reserve and how much of it was airdropped
+    pub fn get_reserve(&self) -> Reserve {
+        self.reserve
+    }
+
+    /*
+        mint random tokens out of the reserve to each recipient, without charging anything and regardless of the sale schedule.
+        This needs the minter role. The storage used by the tokens is paid for by the contract.
+    */
+    pub fn nft_airdrop(&mut self, recipients: Vec<(AccountId, u32)>) -> Vec<TokenId> {
+        self.assert_role(Role::Minter);
+        let quantity = recipients.iter().map(|(_, quantity)| *quantity).sum();
+        self.assert_airdrop_allowed(recipients.len(), quantity);
+
+        let mut mint_logs = Vec::with_capacity(recipients.len());
+        for (receiver_id, quantity) in recipients {
+            assert!(quantity > 0, "Quantity must be greater than 0");
+            let (token_ids, _) = self.internal_mint_batch(&receiver_id, quantity, None, 0);
+            mint_logs.push(NftMintLog {
+                owner_id: receiver_id.to_string(),
+                token_ids,
+                memo: None,
+            });
+        }
+
+        self.reserve.minted += quantity;
+        let token_ids = mint_logs.iter().flat_map(|mint_log| mint_log.token_ids.clone()).collect();
+        log_nft_mint(mint_logs);
+        token_ids
+    }
+
+    //mint specific tokens out of the reserve to each recipient, just like nft_airdrop
+    pub fn nft_airdrop_ids(&mut self, recipients: Vec<(AccountId, Vec<u16>)>) -> Vec<TokenId> {
+        self.assert_role(Role::Minter);
+        let quantity = recipients.iter().map(|(_, token_ids)| token_ids.len() as u32).sum();
+        self.assert_airdrop_allowed(recipients.len(), quantity);
+
+        let mut mint_logs = Vec::with_capacity(recipients.len());
+        for (receiver_id, my_token_ids) in recipients {
+            assert!(!my_token_ids.is_empty(), "Quantity must be greater than 0");
+            let mut token_ids = Vec::with_capacity(my_token_ids.len());
+            for my_token_id in my_token_ids {
+                self.internal_take_token_id(my_token_id);
+                let (token_id, _) = self.internal_mint_token(my_token_id, &receiver_id, None);
+                token_ids.push(token_id);
+            }
+            mint_logs.push(NftMintLog {
+                owner_id: receiver_id.to_string(),
+                token_ids,
+                memo: None,
+            });
+        }
+
+        self.reserve.minted += quantity;
+        let token_ids = mint_logs.iter().flat_map(|mint_log| mint_log.token_ids.clone()).collect();
+        log_nft_mint(mint_logs);
+        token_ids
+    }
+}
+
+impl Contract {
+    //get the number of tokens that can still be minted outside of airdrops, keeping the unused reserve aside
+    pub(crate) fn internal_sale_available_count(&self) -> u32 {
+        self.internal_available_count()
+            .saturating_sub(self.reserve.max - self.reserve.minted)
+    }
+
+    //make sure an airdrop of the given quantity fits in the reserve and in the pool
+    pub(crate) fn assert_airdrop_allowed(&self, recipients: usize, quantity: u32) {
+        self.assert_minting_not_paused();
+        assert!(recipients > 0, "Airdrop must have at least one recipient");
+        let reserve_left = self.reserve.max - self.reserve.minted;
+        assert!(
+            quantity <= reserve_left,
+            "Exceeds the reserve: {} tokens left",
+            reserve_left
+        );
+        assert!(
+            self.internal_available_count() >= quantity,
+            "Exceeds max nfts"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::{accounts, get_logs};
+
+    #[test]
+    fn airdrop_logs_one_event() {
+        let mut contract = setup_contract(5);
+        let token_ids = contract.nft_airdrop(vec![(accounts(1), 2), (accounts(2), 1)]);
+        assert_eq!(token_ids.len(), 3);
+        assert_eq!(contract.get_reserve().minted, 3);
+        assert_eq!(get_logs().len(), 1);
+    }
+
+    #[test]
+    fn airdrop_specific_ids() {
+        let mut contract = setup_contract(5);
+        let token_ids = contract.nft_airdrop_ids(vec![(accounts(1), vec![1, 2])]);
+        assert_eq!(token_ids, vec!["1".to_string(), "2".to_string()]);
+        assert!(!contract.get_remaining_ids(None, None).contains(&1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Exceeds the reserve: 5 tokens left")]
+    fn airdrop_is_capped_by_the_reserve() {
+        let mut contract = setup_contract(5);
+        contract.nft_airdrop(vec![(accounts(1), 6)]);
+    }
+
+    #[test]
+    fn reserve_is_held_back_from_the_sale() {
+        let mut contract = setup_contract(5);
+        assert_eq!(contract.get_remaining_count(), 661);
+        contract.nft_airdrop(vec![(accounts(1), 2)]);
+        assert_eq!(contract.get_remaining_count(), 661);
+        assert_eq!(contract.get_mint_allowance(accounts(1), None), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Exceeds the 666 tokens left")]
+    fn reserve_is_capped_by_the_pool() {
+        setup_contract(667);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires the minter role")]
+    fn airdrops_need_the_minter_role() {
+        let mut contract = setup_contract(5);
+        contract.grant_role(Role::Minter, accounts(1));
+        contract.grant_role(Role::Pauser, accounts(2));
+        set_caller(accounts(1), 0);
+        assert_eq!(contract.nft_airdrop(vec![(accounts(1), 1)]).len(), 1);
+        set_caller(accounts(2), 0);
+        contract.nft_airdrop(vec![(accounts(2), 1)]);
+    }
+}
@@ -4,7 +4,7 @@ use near_sdk::{ext_contract, Gas};
 const GAS_FOR_NFT_APPROVE: Gas = Gas(10_000_000_000_000);
 const NO_DEPOSIT: Balance = 0;
 
-pub trait NonFungibleTokenCore {
+pub trait NonFungibleTokenApproval {
     //approve an account ID to transfer a token on your behalf
     fn nft_approve(&mut self, token_id: TokenId, account_id: AccountId, msg: Option<String>);
 
@@ -36,7 +36,7 @@ trait NonFungibleTokenApprovalsReceiver {
 }
 
 #[near_bindgen]
-impl NonFungibleTokenCore for Contract {
+impl NonFungibleTokenApproval for Contract {
 
     //allow a specific account ID to approve a token on your behalf
     #[payable]
@@ -46,6 +46,8 @@ impl NonFungibleTokenCore for Contract {
             The user needs to attach enough to pay for storage on the contract
         */
         assert_at_least_one_yocto();
+        //make sure approvals aren't paused
+        self.assert_approvals_not_paused();
 
         //get the token object from the token ID
         let mut token = self.tokens_by_id.get(&token_id).expect("No token");
@@ -82,7 +84,7 @@ impl NonFungibleTokenCore for Contract {
         self.tokens_by_id.insert(&token_id, &token);
 
         //refund any excess storage attached by the user. If the user didn't attach enough, panic. 
-        refund_deposit(storage_used);
+        refund_deposit(storage_used, 0);
 
         //if some message was passed into the function, we initiate a cross contract call on the
         //account we're giving access to. 
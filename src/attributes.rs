@@ -0,0 +1,238 @@
+use crate::*;
+
+//the trait type under which the tier of a token is listed
+pub const CLASS_TRAIT_TYPE: &str = "Class";
+//maximum number of attributes a single token can have
+const MAX_TOKEN_ATTRIBUTES: usize = 20;
+
+#[near_bindgen]
+impl Contract {
+    /*
+        set the attributes of the given artwork IDs, replacing the ones they had. The tokens showing them don't need to be minted yet.
+        The class of a token comes from its tier and can't be set here. Attributes are locked once the artwork is revealed
+        or the metadata is frozen, and can still be uploaded once the seed is revealed to stay out of sight until the reveal.
+    */
+    pub fn set_token_attributes(&mut self, attributes: HashMap<TokenId, Vec<TokenAttribute>>) {
+        self.assert_role(Role::Admin);
+        assert!(
+            self.reveal.revealed_at.is_none(),
+            "Attributes cannot be changed once the artwork is revealed"
+        );
+        assert!(!self.metadata_frozen, "Metadata is frozen");
+
+        for (token_id, token_attributes) in attributes {
+            //the attributes are looked up by the artwork a token shows, so the keys must be artwork IDs of the collection
+            let artwork_id = token_id.parse().unwrap_or(0);
+            self.assert_in_collection(artwork_id);
+            self.internal_set_token_attributes(&artwork_id.to_string(), token_attributes);
+        }
+    }
+
+    //get the attributes of a token, starting with its class. Nothing is shown until the artwork is revealed
+    pub fn get_token_attributes(&self, token_id: TokenId) -> Vec<TokenAttribute> {
+        if self.internal_artwork_hidden() {
+            return vec![];
+        }
+        //the attributes belong to the artwork the token shows
+        let artwork_id = match self.internal_artwork_key(&token_id) {
+            Some(artwork_id) => artwork_id,
+            None => return vec![],
+        };
+        let token_type = match self.tokens_by_id.get(&token_id) {
+            Some(token) => token.token_type,
+            //tokens that weren't minted yet get the class of the tier their artwork belongs to
+            None => artwork_id.parse().ok()
+                .and_then(|artwork_id| self.internal_token_tier(artwork_id))
+                .map(|(name, _)| name),
+        };
+        self.internal_token_attributes(&artwork_id, token_type.as_ref())
+    }
+
+    //Query for the minted tokens with the given trait value using pagination
+    pub fn nft_tokens_for_trait(
+        &self,
+        trait_type: String,
+        value: String,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonToken> {
+        //the traits, the class included, stay hidden until the artwork is revealed
+        if self.internal_artwork_hidden() {
+            return vec![];
+        }
+
+        //the class of a token is its type
+        if trait_type == CLASS_TRAIT_TYPE {
+            return self.nft_tokens_for_type(value, from_index, limit);
+        }
+
+        //get the set of tokens with the trait value. If there isn't one, we'll simply return an empty vector
+        let tokens = match self.tokens_per_trait.get(&hash_trait(&trait_type, &value)) {
+            Some(tokens) => tokens,
+            None => return vec![],
+        };
+
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        //iterate through the tokens showing the artwork that were minted, skipping the ones that weren't
+        tokens.iter()
+            .filter_map(|artwork_id| self.internal_artwork_token_key(&artwork_id))
+            .filter_map(|token_id| self.nft_token(token_id))
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+}
+
+impl Contract {
+    //replace the attributes of an artwork, keeping the trait index up to date
+    pub(crate) fn internal_set_token_attributes(&mut self, token_id: &TokenId, token_attributes: Vec<TokenAttribute>) {
+        self.assert_artwork_not_frozen(token_id);
+        assert_valid_attributes(&token_attributes);
+
+        //move the token from the index of its old traits to the index of its new ones
+        for attribute in self.attributes_by_id.get(token_id).unwrap_or_default() {
+            self.internal_remove_token_from_trait(&attribute, token_id);
+        }
+        for attribute in token_attributes.iter() {
+            self.internal_add_token_to_trait(attribute, token_id);
+        }
+        if token_attributes.is_empty() {
+            self.attributes_by_id.remove(token_id);
+        } else {
+            self.attributes_by_id.insert(token_id, &token_attributes);
+        }
+    }
+
+    //get the attributes of an artwork shown by a token of the given type, starting with its class
+    pub(crate) fn internal_token_attributes(&self, token_id: &TokenId, token_type: Option<&TokenType>) -> Vec<TokenAttribute> {
+        let class = token_type.map(|token_type| TokenAttribute {
+            trait_type: CLASS_TRAIT_TYPE.to_string(),
+            value: token_type.clone(),
+            display_type: None,
+        });
+        class.into_iter()
+            .chain(self.attributes_by_id.get(token_id).unwrap_or_default())
+            .collect()
+    }
+
+    //serialize the attributes of an artwork shown by a token of the given type into the JSON of the `extra` field of its metadata
+    pub(crate) fn internal_token_extra(&self, token_id: &TokenId, token_type: Option<&TokenType>) -> Option<String> {
+        let attributes = self.internal_token_attributes(token_id, token_type);
+        if attributes.is_empty() {
+            return None;
+        }
+        Some(serde_json::to_string(&TokenExtra { attributes }).unwrap())
+    }
+
+    //add a token to the set of tokens with the given trait value
+    pub(crate) fn internal_add_token_to_trait(&mut self, attribute: &TokenAttribute, token_id: &TokenId) {
+        let trait_hash = hash_trait(&attribute.trait_type, &attribute.value);
+        let mut tokens_set = self.tokens_per_trait.get(&trait_hash).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::TokensPerTraitInner { trait_hash }.try_to_vec().unwrap())
+        });
+        tokens_set.insert(token_id);
+        self.tokens_per_trait.insert(&trait_hash, &tokens_set);
+    }
+
+    //remove a token from the set of tokens with the given trait value
+    pub(crate) fn internal_remove_token_from_trait(&mut self, attribute: &TokenAttribute, token_id: &TokenId) {
+        let trait_hash = hash_trait(&attribute.trait_type, &attribute.value);
+        if let Some(mut tokens_set) = self.tokens_per_trait.get(&trait_hash) {
+            tokens_set.remove(token_id);
+            if tokens_set.is_empty() {
+                self.tokens_per_trait.remove(&trait_hash);
+            } else {
+                self.tokens_per_trait.insert(&trait_hash, &tokens_set);
+            }
+        }
+    }
+}
+
+//used to generate a unique key for a trait value
+pub(crate) fn hash_trait(trait_type: &str, value: &str) -> CryptoHash {
+    let mut hash = CryptoHash::default();
+    //the trait type and value are separated so ("ab", "c") and ("a", "bc") don't collide
+    hash.copy_from_slice(&env::sha256(&[trait_type.as_bytes(), &[0], value.as_bytes()].concat()));
+    hash
+}
+
+//make sure the attributes of a token are well formed
+fn assert_valid_attributes(attributes: &[TokenAttribute]) {
+    assert!(
+        attributes.len() <= MAX_TOKEN_ATTRIBUTES,
+        "Cannot set more than {} attributes on a token",
+        MAX_TOKEN_ATTRIBUTES
+    );
+    for (index, attribute) in attributes.iter().enumerate() {
+        assert!(!attribute.trait_type.is_empty(), "Trait type cannot be empty");
+        assert!(
+            attribute.trait_type != CLASS_TRAIT_TYPE,
+            "The class of a token comes from its tier"
+        );
+        assert!(
+            attributes[..index].iter().all(|other| other.trait_type != attribute.trait_type),
+            "Duplicate trait type {}",
+            attribute.trait_type
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    #[test]
+    fn attributes_are_serialized_into_extra() {
+        let mut contract = setup_contract(1);
+        contract.set_token_attributes(HashMap::from([("5".to_string(), vec![attribute("Hat", "Crown")])]));
+        contract.nft_airdrop_ids(vec![(accounts(1), vec![5])]);
+
+        let metadata = contract.nft_token("5".to_string()).unwrap().metadata;
+        assert_eq!(
+            metadata.extra.unwrap(),
+            r#"{"attributes":[{"trait_type":"Class","value":"gold"},{"trait_type":"Hat","value":"Crown"}]}"#
+        );
+    }
+
+    #[test]
+    fn tokens_are_filtered_by_trait() {
+        let mut contract = setup_with_tokens(vec![5, 6]);
+        contract.set_token_attributes(HashMap::from([
+            ("5".to_string(), vec![attribute("Hat", "Crown")]),
+            ("6".to_string(), vec![attribute("Hat", "Cap")]),
+            ("7".to_string(), vec![attribute("Hat", "Crown")]),
+        ]));
+
+        let tokens = contract.nft_tokens_for_trait("Hat".to_string(), "Crown".to_string(), None, None);
+        assert_eq!(tokens.iter().map(|token| token.token_id.clone()).collect::<Vec<_>>(), vec!["5".to_string()]);
+        assert_eq!(contract.nft_tokens_for_trait("Class".to_string(), "gold".to_string(), None, None).len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "The class of a token comes from its tier")]
+    fn class_cannot_be_set() {
+        let mut contract = setup_contract(0);
+        contract.set_token_attributes(HashMap::from([("5".to_string(), vec![attribute("Class", "red")])]));
+    }
+
+    #[test]
+    fn attributes_are_stored_under_the_canonical_artwork_id() {
+        let mut contract = setup_with_tokens(vec![5]);
+        contract.set_token_attributes(HashMap::from([("05".to_string(), vec![attribute("Hat", "Crown")])]));
+        assert!(contract.attributes_by_id.get(&"05".to_string()).is_none());
+        assert_eq!(contract.get_token_attributes("5".to_string())[1].value, "Crown");
+    }
+
+    #[test]
+    #[should_panic(expected = "Token 667 is outside of the collection")]
+    fn attributes_are_set_within_the_collection() {
+        let mut contract = setup_contract(0);
+        contract.set_token_attributes(HashMap::from([("667".to_string(), vec![attribute("Hat", "Crown")])]));
+    }
+}
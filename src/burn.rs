@@ -0,0 +1,129 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /*
+        burn a token. This can be done by the token owner or by an account approved on the token.
+        The storage of the token stays with the contract since the owner didn't necessarily pay for it, ex. when it was
+        airdropped or bought from someone else. Only the storage of the approvals, which the owner paid for, is refunded
+    */
+    #[payable]
+    pub fn nft_burn(&mut self, token_id: TokenId, memo: Option<String>) {
+        //assert that the user attached exactly 1 yoctoNEAR for security reasons
+        assert_one_yocto();
+
+        //get the token object and make sure the caller is allowed to burn it
+        let token = self.tokens_by_id.get(&token_id).expect("No token");
+        let sender_id = env::predecessor_account_id();
+        let mut authorized_id = None;
+        if sender_id != token.owner_id {
+            if !token.approved_account_ids.contains_key(&sender_id) {
+                env::panic_str("Unauthorized");
+            }
+            authorized_id = Some(sender_id.to_string());
+        }
+
+        //remove the token from every collection that keeps track of it
+        self.tokens_by_id.remove(&token_id);
+        self.token_metadata_by_id.remove(&token_id);
+        self.internal_remove_token_from_owner(&token.owner_id, &token_id);
+        if let Some(token_type) = token.token_type.as_ref() {
+            self.internal_remove_token_from_type(token_type, &token_id);
+        }
+        self.frozen_token_ids.remove(&token_id);
+
+        //we refund the owner for releasing the storage used up by the approved account IDs
+        refund_approved_account_ids(token.owner_id.clone(), &token.approved_account_ids);
+
+        // Construct the burn log as per the events standard.
+        let nft_burn_log: EventLog = EventLog {
+            // Standard name ("nep171").
+            standard: NFT_STANDARD_NAME.to_string(),
+            // Version of the standard ("nft-1.0.0").
+            version: NFT_METADATA_SPEC.to_string(),
+            // The data related with the event stored in a vector.
+            event: EventLogVariant::NftBurn(vec![NftBurnLog {
+                // Owner of the token.
+                owner_id: token.owner_id.to_string(),
+                // Vector of token IDs that were burned.
+                token_ids: vec![token_id],
+                // The optional approved account ID that burned the token on behalf of the owner.
+                authorized_id,
+                // An optional memo to include.
+                memo,
+            }]),
+        };
+
+        // Log the serialized json.
+        env::log_str(&nft_burn_log.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{accounts, get_created_receipts};
+
+    fn setup() -> Contract {
+        let mut contract = setup_contract(1);
+        contract.set_token_attributes(HashMap::from([("5".to_string(), vec![attribute("Hat", "Crown")])]));
+        contract.nft_airdrop_ids(vec![(accounts(1), vec![5])]);
+        contract
+    }
+
+    #[test]
+    fn burned_token_is_removed_from_every_index() {
+        let mut contract = setup();
+        assert_eq!(contract.nft_tokens_for_trait("Hat".to_string(), "Crown".to_string(), None, None).len(), 1);
+
+        set_caller(accounts(1), 1);
+        contract.nft_burn("5".to_string(), None);
+
+        assert!(contract.tokens_by_id.get(&"5".to_string()).is_none());
+        assert!(contract.token_metadata_by_id.get(&"5".to_string()).is_none());
+        assert!(contract.nft_token("5".to_string()).is_none());
+        assert_eq!(contract.nft_total_supply().0, 0);
+        assert_eq!(contract.nft_supply_for_owner(accounts(1)).0, 0);
+        assert!(contract.nft_tokens_for_owner(accounts(1), None, None).is_empty());
+        assert_eq!(contract.nft_supply_for_type("gold".to_string()).0, 0);
+        assert!(contract.nft_tokens_for_type("gold".to_string(), None, None).is_empty());
+        assert!(contract.nft_tokens_for_trait("Hat".to_string(), "Crown".to_string(), None, None).is_empty());
+    }
+
+    #[test]
+    fn frozen_token_is_forgotten_once_burned() {
+        let mut contract = setup();
+        contract.set_content_hashes(HashMap::from([("5".to_string(), ContentHashes {
+            media_hash: Base64VecU8(vec![1; 32]),
+            reference_hash: Some(Base64VecU8(vec![2; 32])),
+        })]));
+        contract.freeze_metadata(Some(vec!["5".to_string()]));
+
+        set_caller(accounts(1), 1);
+        contract.nft_burn("5".to_string(), None);
+        assert!(!contract.frozen_token_ids.contains(&"5".to_string()));
+    }
+
+    #[test]
+    fn burn_refunds_only_the_storage_of_the_approvals() {
+        let mut contract = setup();
+        set_caller(accounts(1), ONE_NEAR);
+        contract.nft_approve("5".to_string(), accounts(2), None);
+        set_caller(accounts(1), 1);
+        contract.nft_burn("5".to_string(), None);
+
+        //the airdropped token was paid for by the contract, which keeps its storage
+        let refunded: Balance = get_created_receipts()
+            .into_iter()
+            .filter(|receipt| receipt.receiver_id == accounts(1))
+            .flat_map(|receipt| receipt.actions)
+            .filter_map(|action| match action {
+                VmAction::Transfer { deposit } => Some(deposit),
+                _ => None,
+            })
+            .sum();
+        assert_eq!(refunded, Balance::from(bytes_for_approved_account_id(&accounts(2))) * env::storage_byte_cost());
+    }
+}
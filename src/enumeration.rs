@@ -73,4 +73,63 @@ impl Contract {
             //since we turned the keys into an iterator, we need to turn it back into a vector to return
             .collect()
     }
+
+    //get the total supply of NFTs of a given type
+    pub fn nft_supply_for_type(
+        &self,
+        token_type: TokenType,
+    ) -> U128 {
+        //the type of a token is its class, which stays hidden until the artwork is revealed
+        if self.internal_artwork_hidden() {
+            return U128(0);
+        }
+
+        //get the set of tokens for the passed in type
+        let tokens_for_type_set = self.tokens_per_type.get(&token_type);
+
+        //if there is some set of tokens, we'll return the length as a U128
+        if let Some(tokens_for_type_set) = tokens_for_type_set {
+            U128(tokens_for_type_set.len() as u128)
+        } else {
+            //if there isn't a set of tokens for the passed in type, we'll return 0
+            U128(0)
+        }
+    }
+
+    //Query for all the tokens of a type
+    pub fn nft_tokens_for_type(
+        &self,
+        token_type: TokenType,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonToken> {
+        //the type of a token is its class, which stays hidden until the artwork is revealed
+        if self.internal_artwork_hidden() {
+            return vec![];
+        }
+
+        //get the set of tokens for the passed in type
+        let tokens_for_type_set = self.tokens_per_type.get(&token_type);
+        //if there is some set of tokens, we'll set the tokens variable equal to that set
+        let tokens = if let Some(tokens_for_type_set) = tokens_for_type_set {
+            tokens_for_type_set
+        } else {
+            //if there is no set of tokens, we'll simply return an empty vector.
+            return vec![];
+        };
+
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        //iterate through the keys vector
+        tokens.iter()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            //we'll map the token IDs which are strings into Json Tokens
+            .map(|token_id| self.nft_token(token_id.clone()).unwrap())
+            //since we turned the keys into an iterator, we need to turn it back into a vector to return
+            .collect()
+    }
 }
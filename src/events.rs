@@ -1,9 +1,10 @@
+use std::collections::HashMap;
 use std::fmt;
 
 use near_sdk::serde::{Deserialize, Serialize};
 
 /// Enum that represents the data type of the EventLog.
-/// The enum can either be an NftMint or an NftTransfer.
+/// The enum can either be an NftMint, an NftTransfer, an NftBurn or one of the events specific to this contract.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "event", content = "data")]
 #[serde(rename_all = "snake_case")]
@@ -12,6 +13,16 @@ use near_sdk::serde::{Deserialize, Serialize};
 pub enum EventLogVariant {
     NftMint(Vec<NftMintLog>),
     NftTransfer(Vec<NftTransferLog>),
+    NftBurn(Vec<NftBurnLog>),
+    NftMetadataUpdate(Vec<NftMetadataUpdateLog>),
+    ContractMetadataUpdate(Vec<ContractMetadataUpdateLog>),
+    RoyaltyUpdate(Vec<RoyaltyUpdateLog>),
+    PauseUpdate(Vec<PauseUpdateLog>),
+    OwnershipTransferProposed(Vec<OwnershipTransferLog>),
+    OwnershipTransferAccepted(Vec<OwnershipTransferLog>),
+    OwnershipTransferCancelled(Vec<OwnershipTransferLog>),
+    RoleGranted(Vec<RoleLog>),
+    RoleRevoked(Vec<RoleLog>),
 }
 
 /// Interface to capture data about an event
@@ -78,6 +89,105 @@ pub struct NftTransferLog {
     pub memo: Option<String>,
 }
 
+/// An event log to capture token burning
+///
+/// Arguments
+/// * `owner_id`: owner of tokens to burn
+/// * `token_ids`: ["1", "abc"]
+/// * `authorized_id`: approved account to burn, if applicable
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftBurnLog {
+    pub owner_id: String,
+    pub token_ids: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture token metadata updates
+///
+/// Arguments
+/// * `token_ids`: ["1", "abc"], or [] when the metadata of every token changed
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftMetadataUpdateLog {
+    pub token_ids: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture contract metadata updates
+///
+/// Arguments
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractMetadataUpdateLog {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture royalty updates
+///
+/// Arguments
+/// * `token_ids`: ["1", "abc"], absent when the collection royalty was updated
+/// * `royalty`: {"dao.near": 700}, null when the token royalty was removed
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoyaltyUpdateLog {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_ids: Option<Vec<String>>,
+
+    pub royalty: Option<HashMap<String, u32>>,
+}
+
+/// An event log to capture changes to the pause state
+///
+/// Arguments
+/// * `minting`: whether minting is paused
+/// * `transfers`: whether transfers are paused
+/// * `approvals`: whether approvals are paused
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PauseUpdateLog {
+    pub minting: bool,
+    pub transfers: bool,
+    pub approvals: bool,
+}
+
+/// An event log to capture the steps of an ownership transfer
+///
+/// Arguments
+/// * `old_owner_id`: "owner.near"
+/// * `new_owner_id`: "dao.near", the proposed owner while the transfer is pending
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OwnershipTransferLog {
+    pub old_owner_id: String,
+    pub new_owner_id: String,
+}
+
+/// An event log to capture roles being granted, revoked or renounced
+///
+/// Arguments
+/// * `role`: "list_manager"
+/// * `account_id`: account the role was granted to or revoked from
+/// * `sender_id`: account that made the change
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoleLog {
+    pub role: String,
+    pub account_id: String,
+    pub sender_id: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,4 +245,98 @@ mod tests {
         };
         assert_eq!(expected, log.to_string());
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn nep_format_burn() {
+        let expected = r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_burn","data":[{"owner_id":"owner.near","token_ids":["1","2"],"authorized_id":"market.near"}]}"#;
+        let log = EventLog {
+            standard: "nep171".to_string(),
+            version: "1.0.0".to_string(),
+            event: EventLogVariant::NftBurn(vec![NftBurnLog {
+                owner_id: "owner.near".to_string(),
+                token_ids: vec!["1".to_string(), "2".to_string()],
+                authorized_id: Some("market.near".to_string()),
+                memo: None,
+            }]),
+        };
+        assert_eq!(expected, log.to_string());
+    }
+
+    #[test]
+    fn nep_format_metadata_update() {
+        let expected = r#"EVENT_JSON:{"standard":"nep171","version":"1.1.0","event":"nft_metadata_update","data":[{"token_ids":["1","2"],"memo":"reveal"}]}"#;
+        let log = EventLog {
+            standard: "nep171".to_string(),
+            version: "1.1.0".to_string(),
+            event: EventLogVariant::NftMetadataUpdate(vec![NftMetadataUpdateLog {
+                token_ids: vec!["1".to_string(), "2".to_string()],
+                memo: Some("reveal".to_string()),
+            }]),
+        };
+        assert_eq!(expected, log.to_string());
+    }
+
+    #[test]
+    fn royalty_update_format() {
+        let expected = r#"EVENT_JSON:{"standard":"mrgiggles","version":"1.0.0","event":"royalty_update","data":[{"royalty":{"dao.near":700}},{"token_ids":["1"],"royalty":null}]}"#;
+        let log = EventLog {
+            standard: "mrgiggles".to_string(),
+            version: "1.0.0".to_string(),
+            event: EventLogVariant::RoyaltyUpdate(vec![
+                RoyaltyUpdateLog {
+                    token_ids: None,
+                    royalty: Some(HashMap::from([("dao.near".to_string(), 700)])),
+                },
+                RoyaltyUpdateLog {
+                    token_ids: Some(vec!["1".to_string()]),
+                    royalty: None,
+                },
+            ]),
+        };
+        assert_eq!(expected, log.to_string());
+    }
+
+    #[test]
+    fn pause_update_format() {
+        let expected = r#"EVENT_JSON:{"standard":"mrgiggles","version":"1.0.0","event":"pause_update","data":[{"minting":true,"transfers":false,"approvals":false}]}"#;
+        let log = EventLog {
+            standard: "mrgiggles".to_string(),
+            version: "1.0.0".to_string(),
+            event: EventLogVariant::PauseUpdate(vec![PauseUpdateLog {
+                minting: true,
+                transfers: false,
+                approvals: false,
+            }]),
+        };
+        assert_eq!(expected, log.to_string());
+    }
+
+    #[test]
+    fn ownership_transfer_format() {
+        let expected = r#"EVENT_JSON:{"standard":"mrgiggles","version":"1.0.0","event":"ownership_transfer_accepted","data":[{"old_owner_id":"owner.near","new_owner_id":"dao.near"}]}"#;
+        let log = EventLog {
+            standard: "mrgiggles".to_string(),
+            version: "1.0.0".to_string(),
+            event: EventLogVariant::OwnershipTransferAccepted(vec![OwnershipTransferLog {
+                old_owner_id: "owner.near".to_string(),
+                new_owner_id: "dao.near".to_string(),
+            }]),
+        };
+        assert_eq!(expected, log.to_string());
+    }
+
+    #[test]
+    fn role_granted_format() {
+        let expected = r#"EVENT_JSON:{"standard":"mrgiggles","version":"1.0.0","event":"role_granted","data":[{"role":"pauser","account_id":"mod.near","sender_id":"owner.near"}]}"#;
+        let log = EventLog {
+            standard: "mrgiggles".to_string(),
+            version: "1.0.0".to_string(),
+            event: EventLogVariant::RoleGranted(vec![RoleLog {
+                role: "pauser".to_string(),
+                account_id: "mod.near".to_string(),
+                sender_id: "owner.near".to_string(),
+            }]),
+        };
+        assert_eq!(expected, log.to_string());
+    }
+}
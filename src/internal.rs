@@ -7,6 +7,20 @@ pub(crate) fn royalty_to_payout(royalty_percentage: u32, amount_to_pay: Balance)
     U128(royalty_percentage as u128 * amount_to_pay / 10_000u128)
 }
 
+//make sure a royalty map adds up to at most 100% and can be paid out within the payout limits of marketplaces
+pub(crate) fn assert_valid_royalty(royalty: &HashMap<AccountId, u32>) {
+    //the token owner is paid out on top of the royalty receivers so we need to leave room for them
+    assert!(
+        royalty.len() <= MAX_ROYALTY_RECEIVERS,
+        "Cannot add more than {} perpetual royalty amounts",
+        MAX_ROYALTY_RECEIVERS
+    );
+    assert!(
+        royalty.values().map(|amount| *amount as u64).sum::<u64>() <= 10_000,
+        "Perpetual royalties cannot add up to more than 10000 basis points"
+    );
+}
+
 //calculate how many bytes the account ID is taking up
 pub(crate) fn bytes_for_approved_account_id(account_id: &AccountId) -> u64 {
     // The extra 4 bytes are coming from Borsh serialization to store the length of the string.
@@ -45,6 +59,15 @@ pub(crate) fn hash_account_id(account_id: &AccountId) -> CryptoHash {
     hash
 }
 
+//used to generate a unique prefix for the set of tokens of a given type
+pub(crate) fn hash_token_type(token_type: &TokenType) -> CryptoHash {
+    //get the default hash
+    let mut hash = CryptoHash::default();
+    //we hash the token type and return it
+    hash.copy_from_slice(&env::sha256(token_type.as_bytes()));
+    hash
+}
+
 //used to make sure the user attached exactly 1 yoctoNEAR
 pub(crate) fn assert_one_yocto() {
     assert_eq!(
@@ -62,18 +85,21 @@ pub(crate) fn assert_at_least_one_yocto() {
     )
 }
 
-//refund the initial deposit based on the amount of storage that was used up
-pub(crate) fn refund_deposit(storage_used: u64) {
-    //get how much it would cost to store the information
-    let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
+//refund the initial deposit based on the amount of storage that was used up and the price that was charged on top of it
+//returns the total amount that was kept from the deposit
+pub(crate) fn refund_deposit(storage_used: u64, price: Balance) -> Balance {
+    //get how much it would cost to store the information and add the price to it
+    let required_cost = env::storage_byte_cost() * Balance::from(storage_used) + price;
     //get the attached deposit
     let attached_deposit = env::attached_deposit();
 
     //make sure that the attached deposit is greater than or equal to the required cost
+    let covered = if price == 0 { "storage" } else { "the price and storage" };
     assert!(
         required_cost <= attached_deposit,
-        "Must attach {} yoctoNEAR to cover storage",
+        "Must attach {} yoctoNEAR to cover {}",
         required_cost,
+        covered,
     );
 
     //get the refund amount from the attached deposit - required cost
@@ -83,6 +109,8 @@ pub(crate) fn refund_deposit(storage_used: u64) {
     if refund > 1 {
         Promise::new(env::predecessor_account_id()).transfer(refund);
     }
+
+    required_cost
 }
 
 impl Contract {
@@ -145,6 +173,57 @@ impl Contract {
         }
     }
 
+    //add a token to the set of tokens of its type (internal method and can't be called directly via CLI).
+    pub(crate) fn internal_add_token_to_type(
+        &mut self,
+        token_type: &TokenType,
+        token_id: &TokenId,
+    ) {
+        //get the set of tokens for the given type
+        let mut tokens_set = self.tokens_per_type.get(token_type).unwrap_or_else(|| {
+            //if the type doesn't have any tokens, we create a new unordered set
+            UnorderedSet::new(
+                StorageKey::TokensPerTypeInner {
+                    //we get a new unique prefix for the collection
+                    token_type_hash: hash_token_type(token_type),
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
+        });
+
+        //we insert the token ID into the set
+        tokens_set.insert(token_id);
+
+        //we insert that set for the given type.
+        self.tokens_per_type.insert(token_type, &tokens_set);
+    }
+
+    //remove a token from the set of tokens of its type (internal method and can't be called directly via CLI).
+    pub(crate) fn internal_remove_token_from_type(
+        &mut self,
+        token_type: &TokenType,
+        token_id: &TokenId,
+    ) {
+        //we get the set of tokens of the type
+        let mut tokens_set = self
+            .tokens_per_type
+            .get(token_type)
+            //if there is no set of tokens for the type, we panic with the following message:
+            .expect("Token should be indexed under its type");
+
+        //we remove the the token_id from the set of tokens
+        tokens_set.remove(token_id);
+
+        //if the token set is now empty, we remove the type from the tokens_per_type collection
+        if tokens_set.is_empty() {
+            self.tokens_per_type.remove(token_type);
+        } else {
+        //if the token set is not empty, we simply insert it back for the type.
+            self.tokens_per_type.insert(token_type, &tokens_set);
+        }
+    }
+
     //transfers the NFT to the receiver_id (internal method and can't be called directly via CLI).
     pub(crate) fn internal_transfer(
         &mut self,
@@ -155,6 +234,9 @@ impl Contract {
         approval_id: Option<u64>,
         memo: Option<String>,
     ) -> Token {
+        //make sure transfers aren't paused
+        self.assert_transfers_not_paused();
+
         //get the token object by passing in the token_id
         let token = self.tokens_by_id.get(token_id).expect("No token");
 
@@ -200,8 +282,10 @@ impl Contract {
             //reset the approval account IDs
             approved_account_ids: Default::default(),
             next_approval_id: token.next_approval_id,
-            //we copy over the royalties from the previous token
+            //we copy over the token's own royalty from the previous token
             royalty: token.royalty.clone(),
+            //the type never changes
+            token_type: token.token_type.clone(),
         };
         //insert that new token into the tokens_by_id, replacing the old entry 
         self.tokens_by_id.insert(token_id, &new_token);
@@ -0,0 +1,91 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /*
+        set the Merkle root of the accounts on the OG list or the whitelist. Passing None removes it.
+        Leaves are the sha256 hash of the account ID and each pair of nodes is hashed in sorted order,
+        so proofs are just the list of sibling hashes from the leaf up to the root.
+    */
+    pub fn set_merkle_root(&mut self, list: SaleList, root: Option<Base64VecU8>) {
+        self.assert_role(Role::ListManager);
+        assert!(list != SaleList::Public, "The public list cannot have a Merkle root");
+        match root {
+            Some(root) => {
+                self.merkle_roots.insert(list, to_crypto_hash(&root));
+            }
+            None => {
+                self.merkle_roots.remove(&list);
+            }
+        }
+    }
+
+    //get the Merkle roots of the lists that have one
+    pub fn get_merkle_roots(&self) -> HashMap<SaleList, Base64VecU8> {
+        self.merkle_roots.iter()
+            .map(|(list, root)| (*list, Base64VecU8(root.to_vec())))
+            .collect()
+    }
+
+    //check if a proof shows that the account is part of the Merkle tree of the given list
+    pub fn verify_merkle_proof(&self, list: SaleList, account_id: AccountId, merkle_proof: Vec<Base64VecU8>) -> bool {
+        match self.merkle_roots.get(&list) {
+            Some(root) => verify_merkle_proof(root, &account_id, &merkle_proof),
+            None => false,
+        }
+    }
+}
+
+//turn a base64 encoded hash passed in by the user into a crypto hash
+pub(crate) fn to_crypto_hash(hash: &Base64VecU8) -> CryptoHash {
+    assert_eq!(hash.0.len(), 32, "Hash must be 32 bytes long");
+    let mut crypto_hash = CryptoHash::default();
+    crypto_hash.copy_from_slice(&hash.0);
+    crypto_hash
+}
+
+//check if the proof leads from the account's leaf to the root
+pub(crate) fn verify_merkle_proof(root: &CryptoHash, account_id: &AccountId, merkle_proof: &[Base64VecU8]) -> bool {
+    let mut node = env::sha256(account_id.as_bytes());
+    for sibling in merkle_proof {
+        //hash the pair in sorted order so the proof doesn't need to say which side each sibling is on
+        node = if node <= sibling.0 {
+            env::sha256(&[node.as_slice(), sibling.0.as_slice()].concat())
+        } else {
+            env::sha256(&[sibling.0.as_slice(), node.as_slice()].concat())
+        };
+    }
+    node.as_slice() == root.as_slice()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(account_id: &str) -> Vec<u8> {
+        env::sha256(account_id.as_bytes())
+    }
+
+    fn hash_pair(a: &[u8], b: &[u8]) -> Vec<u8> {
+        if a <= b {
+            env::sha256(&[a, b].concat())
+        } else {
+            env::sha256(&[b, a].concat())
+        }
+    }
+
+    #[test]
+    fn verifies_proofs_of_a_four_leaf_tree() {
+        let leaves: Vec<Vec<u8>> = ["alice.near", "bob.near", "carol.near", "dave.near"].iter().map(|account_id| leaf(account_id)).collect();
+        let left = hash_pair(&leaves[0], &leaves[1]);
+        let right = hash_pair(&leaves[2], &leaves[3]);
+        let root = to_crypto_hash(&Base64VecU8(hash_pair(&left, &right)));
+
+        let proof = vec![Base64VecU8(leaves[3].clone()), Base64VecU8(left.clone())];
+        assert!(verify_merkle_proof(&root, &"carol.near".parse().unwrap(), &proof));
+        //the same proof doesn't work for another account
+        assert!(!verify_merkle_proof(&root, &"eve.near".parse().unwrap(), &proof));
+        //and an incomplete proof doesn't reach the root
+        assert!(!verify_merkle_proof(&root, &"carol.near".parse().unwrap(), &proof[..1]));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::*;
 pub type TokenId = String;
+pub type TokenType = String;
 //defines the payout type we'll be returning as a part of the royalty standards.
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
@@ -19,6 +20,26 @@ pub struct NFTContractMetadata {
     pub reference_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
 }
 
+impl NFTContractMetadata {
+    //make sure the metadata follows the metadata standard
+    pub fn assert_valid(&self) {
+        assert!(self.spec.starts_with("nft-"), "Spec must be an NFT metadata spec, ex. {}", NFT_METADATA_SPEC);
+        assert!(!self.name.is_empty(), "Name cannot be empty");
+        assert!(!self.symbol.is_empty(), "Symbol cannot be empty");
+        if let Some(icon) = &self.icon {
+            assert!(icon.starts_with("data:"), "Icon must be a data URL");
+        }
+        assert_eq!(
+            self.reference.is_some(),
+            self.reference_hash.is_some(),
+            "Reference and reference hash must be set together"
+        );
+        if let Some(reference_hash) = &self.reference_hash {
+            assert_eq!(reference_hash.0.len(), 32, "Reference hash must be a sha256 hash");
+        }
+    }
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct TokenMetadata {
@@ -36,6 +57,49 @@ pub struct TokenMetadata {
     pub reference_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
 }
 
+impl TokenMetadata {
+    //make sure the metadata follows the metadata standard. Fields that come from the template aren't part of it
+    pub fn assert_valid(&self) {
+        assert!(
+            self.media.is_none() || self.media_hash.is_some(),
+            "Media hash is required when media is set"
+        );
+        assert!(
+            self.reference.is_none() || self.reference_hash.is_some(),
+            "Reference hash is required when reference is set"
+        );
+        for hash in self.media_hash.iter().chain(self.reference_hash.iter()) {
+            assert_eq!(hash.0.len(), 32, "Content hashes must be sha256 hashes");
+        }
+        assert_ne!(self.copies, Some(0), "Copies must be at least 1");
+        if let Some(expires_at) = self.expires_at {
+            for timestamp in [self.issued_at, self.starts_at].iter().flatten() {
+                assert!(*timestamp < expires_at, "Token cannot expire before it is issued or starts being valid");
+            }
+        }
+        if let (Some(issued_at), Some(updated_at)) = (self.issued_at, self.updated_at) {
+            assert!(issued_at <= updated_at, "Token cannot be updated before it is issued");
+        }
+    }
+}
+
+//a single trait of a token, as listed in the `extra` field of its metadata
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenAttribute {
+    pub trait_type: String, // ex. "Class"
+    pub value: String,      // ex. "gold"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_type: Option<String>, // how marketplaces should display the value, ex. "number" or "boost_percentage"
+}
+
+//the JSON stored in the `extra` field of the token metadata
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenExtra {
+    pub attributes: Vec<TokenAttribute>,
+}
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Token {
     //owner of the token
@@ -44,8 +108,10 @@ pub struct Token {
     pub approved_account_ids: HashMap<AccountId, u64>,
     //the next approval ID to give out. 
     pub next_approval_id: u64,
-    //keep track of the royalty percentages for the token in a hash map
-    pub royalty: HashMap<AccountId, u32>,
+    //keep track of the token's own royalty percentages. None means the collection royalty applies
+    pub royalty: Option<HashMap<AccountId, u32>>,
+    //the type of the token, which is the name of its tier. None if the token doesn't belong to a tier
+    pub token_type: Option<TokenType>,
 }
 
 //The Json token is what will be returned from view calls. 
@@ -74,4 +140,76 @@ impl NonFungibleTokenMetadata for Contract {
     fn nft_metadata(&self) -> NFTContractMetadata {
         self.metadata.get().unwrap()
     }
-}
\ No newline at end of file
+}
+
+#[near_bindgen]
+impl Contract {
+    //replace the contract metadata, ex. to move the collection to another gateway or to shrink the icon
+    pub fn set_contract_metadata(&mut self, metadata: NFTContractMetadata) {
+        self.assert_role(Role::Admin);
+        metadata.assert_valid();
+        //the media and reference of the tokens can be relative to the base URI, so changing it would change frozen metadata
+        assert!(
+            !self.metadata_frozen || metadata.base_uri == self.metadata.get().unwrap().base_uri,
+            "The base URI cannot be changed once the metadata is frozen"
+        );
+        self.metadata.set(&metadata);
+
+        // Construct the contract metadata update log as per the events standard.
+        let contract_metadata_update_log: EventLog = EventLog {
+            // Standard name ("nep171").
+            standard: NFT_STANDARD_NAME.to_string(),
+            // Version of the standard that added the event ("1.1.0").
+            version: NFT_UPDATE_EVENT_VERSION.to_string(),
+            // The data related with the event stored in a vector.
+            event: EventLogVariant::ContractMetadataUpdate(vec![ContractMetadataUpdateLog { memo: None }]),
+        };
+
+        // Log the serialized json.
+        env::log_str(&contract_metadata_update_log.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::get_logs;
+
+    #[test]
+    fn contract_metadata_is_updated() {
+        let mut contract = setup_contract(0);
+        contract.set_contract_metadata(NFTContractMetadata {
+            icon: None,
+            reference: Some("https://example.com/collection.json".to_string()),
+            reference_hash: Some(Base64VecU8(vec![1; 32])),
+            ..contract.nft_metadata()
+        });
+        assert!(contract.nft_metadata().icon.is_none());
+        assert_eq!(
+            get_logs(),
+            vec![r#"EVENT_JSON:{"standard":"nep171","version":"1.1.0","event":"contract_metadata_update","data":[{}]}"#]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The base URI cannot be changed once the metadata is frozen")]
+    fn base_uri_is_frozen_with_the_metadata() {
+        let mut contract = setup_contract(0);
+        contract.freeze_metadata(None);
+        contract.set_contract_metadata(NFTContractMetadata {
+            base_uri: Some("https://example.com".to_string()),
+            ..contract.nft_metadata()
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Reference and reference hash must be set together")]
+    fn reference_needs_a_hash() {
+        let mut contract = setup_contract(0);
+        contract.set_contract_metadata(NFTContractMetadata {
+            reference: Some("https://example.com/collection.json".to_string()),
+            ..contract.nft_metadata()
+        });
+    }
+}
@@ -0,0 +1,230 @@
+use crate::*;
+use std::collections::HashSet;
+
+//the number of tokens of the first version of the contract, which had no max supply
+const V1_MAX_SUPPLY: u32 = 666;
+
+//the state of the first version of the contract, before the upgrade
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ContractV1 {
+    pub owner_id: AccountId,
+    pub tokens_per_owner: LookupMap<AccountId, UnorderedSet<TokenId>>,
+    pub tokens_by_id: LookupMap<TokenId, TokenV1>,
+    pub token_metadata_by_id: UnorderedMap<TokenId, TokenMetadata>,
+    pub metadata: LazyOption<NFTContractMetadata>,
+    pub whitelist: LookupMap<AccountId, bool>,
+    pub oglist: LookupMap<AccountId, bool>,
+    pub presale_minted: LookupMap<AccountId, u128>,
+    pub pubsale_minted: LookupMap<AccountId, u128>,
+    pub token_ids: Vec<u16>,
+}
+
+//a token as stored by the first version of the contract, with its own copy of the collection royalty
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct TokenV1 {
+    pub owner_id: AccountId,
+    pub approved_account_ids: HashMap<AccountId, u64>,
+    pub next_approval_id: u64,
+    pub royalty: HashMap<AccountId, u32>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /*
+        upgrade the state of the first version of the contract. The tokens it stored can't be read until they are
+        rewritten with migrate_tokens, which the owner should call right after the upgrade until no token is left
+    */
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let old: ContractV1 = env::state_read().expect("No state to migrate");
+        let mut this = Self::internal_new(old.owner_id, None, V1_MAX_SUPPLY);
+        this.token_metadata_by_id = old.token_metadata_by_id;
+        this.metadata = old.metadata;
+        this.internal_set_default_collection();
+
+        //the template points to the artwork relative to the gateway
+        let mut metadata = this.metadata.get().unwrap();
+        if metadata.base_uri.is_none() {
+            metadata.base_uri = Some("https://ipfs.io/ipfs".to_string());
+            this.metadata.set(&metadata);
+        }
+
+        //rebuild the pool out of the IDs that weren't minted, writing as few slots as possible
+        let minted = V1_MAX_SUPPLY as usize - old.token_ids.len();
+        if old.token_ids.len() <= minted {
+            for token_id in old.token_ids {
+                this.token_ids.push(token_id);
+            }
+        } else {
+            this.token_ids = TokenPool::with_range(1, V1_MAX_SUPPLY as u16);
+            let remaining: HashSet<u16> = old.token_ids.into_iter().collect();
+            for token_id in (1..=V1_MAX_SUPPLY as u16).filter(|token_id| !remaining.contains(token_id)) {
+                this.token_ids.take(token_id);
+            }
+        }
+        this.token_ids_added = V1_MAX_SUPPLY;
+
+        this.legacy_tokens_left = this.token_metadata_by_id.len();
+        this
+    }
+
+    /*
+        rewrite up to `limit` tokens stored by the first version of the contract. The collection royalty they copied is
+        dropped so the one of the contract applies, and they get the tier of their ID. The metadata fields that come from
+        the template, the content hashes and the attributes are cleared, since the first version wrote full URLs, made-up
+        hashes and a hand-written `extra` into every token. Returns how many tokens are left
+    */
+    pub fn migrate_tokens(&mut self, limit: u64) -> u64 {
+        self.assert_owner();
+        let mut old_tokens: LookupMap<TokenId, TokenV1> = LookupMap::new(StorageKey::TokensById.try_to_vec().unwrap());
+
+        /*
+            the tokens left are the first ones of the metadata map. Tokens minted since the upgrade are pushed after them,
+            and burning one only ever moves a rewritten token into its place
+        */
+        let to = self.legacy_tokens_left;
+        let from = to.saturating_sub(limit);
+        let token_ids: Vec<TokenId> = (from..to)
+            .map(|index| self.token_metadata_by_id.keys_as_vector().get(index).unwrap())
+            .collect();
+        for token_id in token_ids {
+            //the old token is taken out first since replacing it would read it with the new layout
+            let old_token = old_tokens.remove(&token_id).unwrap();
+            let royalty = Some(old_token.royalty).filter(|royalty| *royalty != self.default_royalty);
+            let token_type = token_id.parse().ok()
+                .and_then(|token_id| self.internal_artwork_id(token_id))
+                .and_then(|artwork_id| self.internal_record_tier_mint(artwork_id))
+                .map(|(name, _)| name);
+            if let Some(token_type) = &token_type {
+                self.internal_add_token_to_type(token_type, &token_id);
+            }
+
+            //only the fields the template doesn't provide are kept, ex. when the token was issued
+            let metadata = self.token_metadata_by_id.get(&token_id).unwrap();
+            self.token_metadata_by_id.insert(&token_id, &TokenMetadata {
+                title: None,
+                description: None,
+                media: None,
+                media_hash: None,
+                extra: None,
+                reference: None,
+                reference_hash: None,
+                ..metadata
+            });
+            self.tokens_by_id.insert(&token_id, &Token {
+                owner_id: old_token.owner_id,
+                approved_account_ids: old_token.approved_account_ids,
+                next_approval_id: old_token.next_approval_id,
+                royalty,
+                token_type,
+            });
+        }
+        self.legacy_tokens_left = from;
+        from
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::hash_account_id;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    //write the state of the first version of the contract with tokens 5 and 130 minted to accounts(1)
+    fn setup_v1() {
+        set_caller(accounts(0), 0);
+        let mut old = ContractV1 {
+            owner_id: accounts(0),
+            tokens_per_owner: LookupMap::new(StorageKey::TokensPerOwner.try_to_vec().unwrap()),
+            tokens_by_id: LookupMap::new(StorageKey::TokensById.try_to_vec().unwrap()),
+            token_metadata_by_id: UnorderedMap::new(StorageKey::TokenMetadataById.try_to_vec().unwrap()),
+            metadata: LazyOption::new(StorageKey::NFTContractMetadata.try_to_vec().unwrap(), Some(&NFTContractMetadata {
+                spec: NFT_METADATA_SPEC.to_string(),
+                name: "Mr Giggles".to_string(),
+                symbol: "MrG".to_string(),
+                icon: None,
+                base_uri: None,
+                reference: None,
+                reference_hash: None,
+            })),
+            whitelist: LookupMap::new(StorageKey::Whitelist.try_to_vec().unwrap()),
+            oglist: LookupMap::new(StorageKey::Oglist.try_to_vec().unwrap()),
+            presale_minted: LookupMap::new(StorageKey::PresaleMinted.try_to_vec().unwrap()),
+            pubsale_minted: LookupMap::new(StorageKey::PubsaleMinted.try_to_vec().unwrap()),
+            token_ids: (1..=666).filter(|token_id| *token_id != 5 && *token_id != 130).collect(),
+        };
+        let mut tokens_set = UnorderedSet::new(
+            StorageKey::TokenPerOwnerInner { account_id_hash: hash_account_id(&accounts(1)) }.try_to_vec().unwrap(),
+        );
+        for (token_id, royalty) in [("5", 700), ("130", 500)] {
+            old.tokens_by_id.insert(&token_id.to_string(), &TokenV1 {
+                owner_id: accounts(1),
+                approved_account_ids: HashMap::new(),
+                next_approval_id: 0,
+                royalty: HashMap::from([("mrgiggles.near".parse().unwrap(), royalty)]),
+            });
+            //the metadata as the first version of nft_mint wrote it
+            old.token_metadata_by_id.insert(&token_id.to_string(), &TokenMetadata {
+                title: Some(format!("Mr Giggles #{}", token_id)),
+                description: Some("Mr Giggles is an art NFT project".to_string()),
+                media: Some(format!("https://ipfs.io/ipfs/QmXXsDoynSQpinPRSqu6RGJwSHkkSFA1k2xX7r8kco557P/{}.gif", token_id)),
+                media_hash: Some(Base64VecU8(b"VGhpcyBpcyBtZWRpYSBoYXNoLg==".to_vec())),
+                copies: Some(1),
+                issued_at: Some(1656788400000),
+                expires_at: None,
+                starts_at: None,
+                updated_at: None,
+                extra: Some(r#"{"attributes": [{"trait_type": "Class", "value": "gold" }]}"#.to_string()),
+                reference: Some(format!("https://ipfs.io/ipfs/QmeUL6QkHZKMPdWRwb8kWmQMbgdTbMNpZUegLkpd2Wx4fY/{}.json", token_id)),
+                reference_hash: Some(Base64VecU8(b"QmFzZTY0LWVuY29kZWQgc2hhMjU2IGhhc2ggb2YgSlNPTiBmcm9tIHJlZmVyZW5jZSBmaWVsZC4=".to_vec())),
+            });
+            tokens_set.insert(&token_id.to_string());
+        }
+        old.tokens_per_owner.insert(&accounts(1), &tokens_set);
+        env::state_write(&old);
+    }
+
+    #[test]
+    fn state_of_the_first_version_is_migrated() {
+        setup_v1();
+        let mut contract = Contract::migrate();
+        assert_eq!(contract.get_remaining_count(), 664);
+        assert_eq!(contract.nft_metadata().base_uri.unwrap(), "https://ipfs.io/ipfs");
+
+        assert_eq!(contract.migrate_tokens(1), 1);
+        assert_eq!(contract.migrate_tokens(10), 0);
+        let token = contract.nft_token("5".to_string()).unwrap();
+        assert_eq!(token.owner_id, accounts(1));
+        assert_eq!(contract.nft_tokens_for_owner(accounts(1), None, None).len(), 2);
+
+        //the metadata follows the template, the content hashes and the attributes, and keeps when the token was issued
+        assert_eq!(token.metadata.issued_at, Some(1656788400000));
+        assert_eq!(token.metadata.media.unwrap(), "QmXXsDoynSQpinPRSqu6RGJwSHkkSFA1k2xX7r8kco557P/5.gif");
+        assert!(token.metadata.media_hash.is_none());
+        contract.set_content_hashes(HashMap::from([("5".to_string(), ContentHashes {
+            media_hash: Base64VecU8(vec![1; 32]),
+            reference_hash: Some(Base64VecU8(vec![2; 32])),
+        })]));
+        contract.set_token_attributes(HashMap::from([("5".to_string(), vec![attribute("Hat", "Crown")])]));
+        let metadata = contract.nft_token("5".to_string()).unwrap().metadata;
+        assert_eq!(metadata.media_hash.unwrap().0, vec![1; 32]);
+        assert!(metadata.extra.unwrap().contains("Crown"));
+
+        //the token can be fixed and frozen like any other
+        set_caller_at(accounts(0), 0, 1_700_000_000_000);
+        contract.nft_update_metadata("5".to_string(), TokenMetadataPatch {
+            title: Some("Mr Giggles the First".to_string()),
+            ..Default::default()
+        });
+        contract.freeze_metadata(Some(vec!["5".to_string()]));
+        assert_eq!(contract.nft_token("5".to_string()).unwrap().metadata.title.unwrap(), "Mr Giggles the First");
+
+        //the tokens that copied the collection royalty follow the one of the contract, the others keep theirs
+        assert!(contract.tokens_by_id.get(&"5".to_string()).unwrap().royalty.is_none());
+        assert_eq!(contract.tokens_by_id.get(&"130".to_string()).unwrap().royalty.unwrap().values().sum::<u32>(), 500);
+        assert_eq!(contract.get_token_tier(130).unwrap(), "red");
+        assert_eq!(contract.nft_supply_for_type("gold".to_string()).0, 1);
+    }
+}
@@ -1,170 +1,63 @@
 use crate::*;
 
+//what was minted and charged by a call to one of the mint methods
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintResult {
+    //the IDs of the tokens that were minted
+    pub token_ids: Vec<TokenId>,
+    //the price of the tokens according to the sale phase, their tiers or the voucher
+    pub price: U128,
+    //the cost of the storage used up by the tokens
+    pub storage_cost: U128,
+    //the total amount kept from the attached deposit. Anything above it is refunded
+    pub charged: U128,
+}
+
 #[near_bindgen]
 impl Contract {
     #[payable]
     pub fn nft_mint(
         &mut self,
-        token_id: Option<TokenId>,
         // metadata: TokenMetadata,
         receiver_id: AccountId,
         //we add an optional parameter for perpetual royalties
-        _perpetual_royalties: Option<HashMap<AccountId, u32>>,
-    ) -> String{
-        let total_supply:u16 = self.token_metadata_by_id.len().try_into().unwrap();
-        assert!(
-            total_supply < 666,
-            "Exceeds max nfts"
-        );
-        let length:u64 = self.token_ids.len().try_into().unwrap();
-        let index:usize = (env::block_timestamp() % length).try_into().unwrap();
-        let my_token_id = self.token_ids[index];
-        self.token_ids.remove(index.try_into().unwrap());
-        let token_type = get_type_by_id(my_token_id);
-        let caller = env::predecessor_account_id();
-        let deposit = env::attached_deposit();
-        let curr_time = env::block_timestamp() / 1_000_000;
-
-        const PRESALE_TIME: u64 = 0; // 2nd July 2022 04:00PM UTC
-        const PUBSALE_TIME: u64 = 1656788400000; // 2nd July 2022 07:00PM UTC
-        const OG_PRICE: u128 = 7_000_000_000_000_000_000_000_000; // 7 $NEAR
-        const WL_PRICE: u128 = 8_000_000_000_000_000_000_000_000; // 8 $NEAR
-        const PUB_PRICE: u128 = 8_000_000_000_000_000_000_000_000; // 8 $NEAR
-        assert!(
-            curr_time >= PRESALE_TIME,
-            "Presale not started"
-        );
-        if curr_time < PUBSALE_TIME {
-            assert!(self.oglist.contains_key(&caller) || self.whitelist.contains_key(&caller), "You are not whitelisted");
-        }
-        if self.oglist.contains_key(&caller) {
-            assert!(
-                deposit >= OG_PRICE,
-                "Insufficient fund"
-            );
-        } else if self.whitelist.contains_key(&caller) {
-            assert!(
-                deposit >= WL_PRICE,
-                "Insufficient fund"
-            );
-        } else {
-            assert!(deposit >= PUB_PRICE, "Insufficient fund")
+        perpetual_royalties: Option<HashMap<AccountId, u32>>,
+        //we add an optional proof that the caller is part of the Merkle tree of the OG list or the whitelist
+        merkle_proof: Option<Vec<Base64VecU8>>,
+    ) -> MintResult {
+        //only royalty managers can override the collection's default royalties
+        if let Some(perpetual_royalties) = perpetual_royalties.as_ref() {
+            self.assert_role(Role::RoyaltyManager);
+            assert_valid_royalty(perpetual_royalties);
         }
+        self.internal_sale_mint(&receiver_id, 1, perpetual_royalties, merkle_proof)
+    }
 
-        let metadata = TokenMetadata {
-            title: Some(format!("Mr Giggles #{}", my_token_id)), // ex. "Arch Nemesis: Mail Carrier" or "Parcel #5055"
-            description: Some(String::from("Mr Giggles is an art NFT project (on the NEAR Protocol blockchain) with lofty aspirations of becoming a thriving DAO. This project is the brainchild of father and son duo: Mr Giggles and Jamma (Mr Giggles Jr.). Mr Giggles is built on community, inviting holders in to make decisions and benefit from being involved in the project.")), // free-form description
-            media: Some(format!("https://ipfs.io/ipfs/QmXXsDoynSQpinPRSqu6RGJwSHkkSFA1k2xX7r8kco557P/{}.gif", my_token_id)), // URL to associated media, preferably to decentralized, content-addressed storage
-            media_hash: Some(Base64VecU8(b"VGhpcyBpcyBtZWRpYSBoYXNoLg==".to_vec())), // Base64-encoded sha256 hash of content referenced by the `media` field. Required if `media` is included.
-            copies: Some(1), // number of copies of this set of metadata in existence when token was minted.
-            issued_at: Some(curr_time), // When token was issued or minted, Unix epoch in milliseconds
-            expires_at: None, // When token expires, Unix epoch in milliseconds
-            starts_at: None, // When token starts being valid, Unix epoch in milliseconds
-            updated_at: None, // When token was last updated, Unix epoch in milliseconds
-            extra: Some(format!("{{\"attributes\": [{{\"trait_type\": \"Class\", \"value\": \"{}\" }}]}}", token_type)), // anything extra the NFT wants to store on-chain. Can be stringified JSON.
-            reference: Some(format!("https://ipfs.io/ipfs/QmeUL6QkHZKMPdWRwb8kWmQMbgdTbMNpZUegLkpd2Wx4fY/{}.json", my_token_id)), // URL to an off-chain JSON file with more info.
-            reference_hash: Some(Base64VecU8(b"QmFzZTY0LWVuY29kZWQgc2hhMjU2IGhhc2ggb2YgSlNPTiBmcm9tIHJlZmVyZW5jZSBmaWVsZC4=".to_vec())), // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
-        };
-
-        let mut final_token_id = format!("{}", my_token_id);
-        if let Some(token_id) = token_id {
-            final_token_id = token_id;
-        }
-        //measure the initial storage being used on the contract
-        // let initial_storage_usage = env::storage_usage();
-
-        // create a royalty map to store in the token
-        let mut royalty = HashMap::new();
-        let royal: AccountId = "mrgiggles.near".parse().unwrap();
-        /*
-        // if perpetual royalties were passed into the function: 
-        if let Some(perpetual_royalties) = perpetual_royalties {
-            //make sure that the length of the perpetual royalties is below 7 since we won't have enough GAS to pay out that many people
-            assert!(perpetual_royalties.len() < 7, "Cannot add more than 6 perpetual royalty amounts");
-
-            //iterate through the perpetual royalties and insert the account and amount in the royalty map
-            for (account, amount) in perpetual_royalties {
-                royalty.insert(account, amount);
-            }
-        }
-        */
-        royalty.insert(royal, 700);
-
-        //specify the token struct that contains the owner ID 
-        let token = Token {
-            //set the owner ID equal to the receiver ID passed into the function
-            owner_id: receiver_id,
-            //we set the approved account IDs to the default value (an empty map)
-            approved_account_ids: Default::default(),
-            //the next approval ID is set to 0
-            next_approval_id: 0,
-            //the map of perpetual royalties for the token (The owner will get 100% - total perpetual royalties)
-            royalty,
-        };
-
-        //insert the token ID and token struct and make sure that the token doesn't exist
-        assert!(
-            self.tokens_by_id.insert(&final_token_id, &token).is_none(),
-            "Token already exists"
-        );
-
-        //insert the token ID and metadata
-        self.token_metadata_by_id.insert(&final_token_id, &metadata);
-
-        //call the internal method for adding the token to the owner
-        self.internal_add_token_to_owner(&token.owner_id, &final_token_id);
-
-        // Construct the mint log as per the events standard.
-        let nft_mint_log: EventLog = EventLog {
-            // Standard name ("nep171").
-            standard: NFT_STANDARD_NAME.to_string(),
-            // Version of the standard ("nft-1.0.0").
-            version: NFT_METADATA_SPEC.to_string(),
-            // The data related with the event stored in a vector.
-            event: EventLogVariant::NftMint(vec![NftMintLog {
-                // Owner of the token.
-                owner_id: token.owner_id.to_string(),
-                // Vector of token IDs that were minted.
-                token_ids: vec![final_token_id.to_string()],
-                // An optional memo to include.
-                memo: None,
-            }]),
-        };
-
-        // Log the serialized json.
-        env::log_str(&nft_mint_log.to_string());
-        if curr_time > PRESALE_TIME && curr_time < PUBSALE_TIME {
-            if self.presale_minted.contains_key(&caller) {
-                let minted = self.presale_minted.get(&caller).unwrap();
-                self.presale_minted.remove(&caller.clone().into());
-                self.presale_minted.insert(&caller.clone().into(), &(minted + 1));
-            } else {
-                self.presale_minted.insert(&caller.clone().into(), &(1));
-            }
-        } else {
-            if self.pubsale_minted.contains_key(&caller) {
-                let minted = self.pubsale_minted.get(&caller).unwrap();
-                self.pubsale_minted.remove(&caller.clone().into());
-                self.pubsale_minted.insert(&caller.clone().into(), &(minted + 1));
-            } else {
-                self.pubsale_minted.insert(&caller.clone().into(), &(1));
-            }
-        }
-        nft_mint_log.to_string()
+    //mint several random tokens at once, paying the price of the caller's sale phase for each of them
+    #[payable]
+    pub fn nft_mint_many(
+        &mut self,
+        receiver_id: AccountId,
+        quantity: u32,
+        merkle_proof: Option<Vec<Base64VecU8>>,
+    ) -> MintResult {
+        assert!(quantity > 0, "Quantity must be greater than 0");
+        self.internal_sale_mint(&receiver_id, quantity, None, merkle_proof)
     }
 
     pub fn add_whitelist(&mut self, account_id: AccountId) {
-        self.assert_owner();
+        self.assert_role(Role::ListManager);
         self.whitelist.insert(&account_id, &(true));
     }
 
     pub fn remove_whitelist(&mut self, account_id: AccountId) {
-        self.assert_owner();
+        self.assert_role(Role::ListManager);
         self.whitelist.remove(&account_id);
     }
 
     pub fn add_whitelists(&mut self, account_ids: Vec<AccountId>) {
-        self.assert_owner();
+        self.assert_role(Role::ListManager);
         for account_id in account_ids {
             if !self.whitelist.contains_key(&account_id) {
                 self.whitelist.insert(&account_id, &(true));
@@ -173,7 +66,7 @@ impl Contract {
     }
 
     pub fn remove_whitelists(&mut self, account_ids: Vec<AccountId>) {
-        self.assert_owner();
+        self.assert_role(Role::ListManager);
         for account_id in account_ids {
             if self.whitelist.contains_key(&account_id) {
                 self.whitelist.remove(&account_id);
@@ -182,17 +75,17 @@ impl Contract {
     }
 
     pub fn add_oglist(&mut self, account_id: AccountId) {
-        self.assert_owner();
+        self.assert_role(Role::ListManager);
         self.oglist.insert(&account_id, &(true));
     }
 
     pub fn remove_oglist(&mut self, account_id: AccountId) {
-        self.assert_owner();
+        self.assert_role(Role::ListManager);
         self.oglist.remove(&account_id);
     }
 
     pub fn add_oglists(&mut self, account_ids: Vec<AccountId>) {
-        self.assert_owner();
+        self.assert_role(Role::ListManager);
         for account_id in account_ids {
             if !self.oglist.contains_key(&account_id) {
                 self.oglist.insert(&account_id, &(true));
@@ -201,7 +94,7 @@ impl Contract {
     }
 
     pub fn remove_oglists(&mut self, account_ids: Vec<AccountId>) {
-        self.assert_owner();
+        self.assert_role(Role::ListManager);
         for account_id in account_ids {
             if self.oglist.contains_key(&account_id) {
                 self.oglist.remove(&account_id);
@@ -217,16 +110,13 @@ impl Contract {
         return self.oglist.contains_key(&account_id);
     }
 
+    //get the state of the sale: 0 when no phase is open, 1 during a presale phase and 2 during a public phase
     pub fn get_sale_state(&self) -> u16 {
         let curr_time = env::block_timestamp() / 1_000_000;
-        const PRESALE_TIME: u64 = 0; // 2nd July 2022 04:00PM UTC
-        const PUBSALE_TIME: u64 = 1656788400000; // 2nd July 2022 07:00PM UTC
-        if curr_time < PRESALE_TIME {
-            return 0;
-        } else if curr_time > PRESALE_TIME && curr_time < PUBSALE_TIME {
-            return 1;
-        } else {
-            return 2;
+        match self.internal_active_phase(curr_time) {
+            None => 0,
+            Some((_, phase)) if !phase.is_public() => 1,
+            Some(_) => 2,
         }
     }
 
@@ -234,8 +124,19 @@ impl Contract {
         return env::block_timestamp() / 1_000_000;
     }
 
-    pub fn get_remaining_ids(&self) -> Vec<u16> {
-        return self.token_ids.clone();
+    /*
+        get the token IDs that haven't been minted yet using pagination. They are listed in pool order, not in the order they will be drawn.
+        IDs of sold-out tiers are left out, so a page can hold fewer IDs than the limit
+    */
+    pub fn get_remaining_ids(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<u16> {
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0))) as u32;
+        //take the first "limit" IDs. If we didn't specify a limit, use 50
+        let end = start.saturating_add(limit.unwrap_or(50) as u32).min(self.token_ids.len());
+        (start..end)
+            .filter_map(|slot| self.token_ids.get(slot))
+            .filter(|token_id| self.internal_tier_available(*token_id))
+            .collect()
     }
 
     pub fn get_total_supply(&self) -> u16 {
@@ -256,16 +157,267 @@ impl Contract {
         return 0;
     }
 
-    pub fn get_metadatas(&self) -> Vec<TokenMetadata> {
-        let mut result:Vec<TokenMetadata> = Vec::new();
-        let total_supply:u16 = self.token_metadata_by_id.len().try_into().unwrap();
-        let mut token_id:TokenId;
-        let mut metadata:TokenMetadata;
-        for i in 1..total_supply + 1 {
-            token_id = format!("{}", i);
-            metadata = self.token_metadata_by_id.get(&token_id).unwrap();
-            result.push(metadata);
+    //get the metadata of the minted tokens, paginated like nft_tokens
+    pub fn get_metadatas(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<TokenMetadata> {
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+        self.token_metadata_by_id.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .map(|(token_id, metadata)| {
+                let token_type = self.tokens_by_id.get(&token_id).and_then(|token| token.token_type);
+                self.internal_resolve_metadata(&token_id, token_type.as_ref(), metadata)
+            })
+            .collect()
+    }
+}
+
+impl Contract {
+    //mint the given quantity of random tokens to the receiver, charging the caller according to the sale schedule
+    pub(crate) fn internal_sale_mint(
+        &mut self,
+        receiver_id: &AccountId,
+        quantity: u32,
+        perpetual_royalties: Option<HashMap<AccountId, u32>>,
+        merkle_proof: Option<Vec<Base64VecU8>>,
+    ) -> MintResult {
+        self.assert_minting_not_paused();
+        assert!(
+            self.internal_sale_available_count() >= quantity,
+            "Exceeds max nfts"
+        );
+        let caller = env::predecessor_account_id();
+        let deposit = env::attached_deposit();
+        let curr_time = env::block_timestamp() / 1_000_000;
+
+        //measure the initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        //find the sale phases the caller is allowed to mint in
+        let eligible_phases = self.internal_eligible_phases(&caller, merkle_proof.as_deref(), curr_time);
+        if eligible_phases.is_empty() {
+            if self.internal_active_phase(curr_time).is_none() {
+                env::panic_str("Sale is not active");
+            }
+            env::panic_str("You are not whitelisted");
+        }
+
+        //make sure the caller doesn't go over their mint limits, spreading the tokens over overlapping phases
+        let split = self.internal_split_between_phases(eligible_phases, &caller, quantity);
+
+        let mut token_ids = Vec::with_capacity(quantity as usize);
+        let mut price = 0;
+        for (phase_id, phase, phase_quantity) in split {
+            //draw and mint each of the tokens. Tokens of a tier with its own price cost that instead of the phase price
+            let (phase_token_ids, phase_price) =
+                self.internal_mint_batch(receiver_id, phase_quantity, perpetual_royalties.clone(), phase.price.0);
+            token_ids.extend(phase_token_ids);
+            price += phase_price;
+
+            //keep track of how many tokens the caller minted in the phase and in the sale as a whole
+            let phase_minted = self.phase_minted.get(&(phase_id, caller.clone())).unwrap_or(0);
+            self.phase_minted.insert(&(phase_id, caller.clone()), &(phase_minted + phase_quantity));
+            self.internal_record_sale_mint(&caller, phase.is_public(), phase_quantity);
+        }
+        assert!(
+            deposit >= price,
+            "Insufficient fund"
+        );
+        log_nft_mint(vec![NftMintLog {
+            owner_id: receiver_id.to_string(),
+            token_ids: token_ids.clone(),
+            memo: None,
+        }]);
+
+        self.internal_charge_mint(token_ids, initial_storage_usage, price)
+    }
+
+    //draw and mint the given quantity of tokens to the receiver and return their total price given the price of tokens without a tier price
+    pub(crate) fn internal_mint_batch(
+        &mut self,
+        receiver_id: &AccountId,
+        quantity: u32,
+        perpetual_royalties: Option<HashMap<AccountId, u32>>,
+        base_price: Balance,
+    ) -> (Vec<TokenId>, Balance) {
+        let mut token_ids = Vec::with_capacity(quantity as usize);
+        let mut price = 0;
+        for _ in 0..quantity {
+            let my_token_id = self.internal_draw_token_id();
+            let (token_id, tier_price) = self.internal_mint_token(my_token_id, receiver_id, perpetual_royalties.clone());
+            price += tier_price.map_or(base_price, |tier_price| tier_price.0);
+            token_ids.push(token_id);
         }
-        return result;
+        (token_ids, price)
+    }
+
+    //keep track of how many tokens the account minted during the presale or the public sale
+    pub(crate) fn internal_record_sale_mint(&mut self, account_id: &AccountId, is_public: bool, quantity: u32) {
+        let sale_minted = if is_public {
+            &mut self.pubsale_minted
+        } else {
+            &mut self.presale_minted
+        };
+        let minted = sale_minted.get(account_id).unwrap_or(0);
+        sale_minted.insert(account_id, &(minted + quantity as u128));
+    }
+
+    //charge the caller for the price and the storage used since the initial storage usage and hand the proceeds to the treasury
+    pub(crate) fn internal_charge_mint(
+        &mut self,
+        token_ids: Vec<TokenId>,
+        initial_storage_usage: u64,
+        price: Balance,
+    ) -> MintResult {
+        //calculate the storage used by the mint and refund anything attached above the price and storage cost
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        let charged = refund_deposit(storage_used, price);
+
+        //hand the proceeds over to the treasury
+        self.internal_collect_revenue(price);
+
+        MintResult {
+            token_ids,
+            price: U128(price),
+            storage_cost: U128(charged - price),
+            charged: U128(charged),
+        }
+    }
+
+    //mint the token drawn from the pool to the receiver and return its token ID and the price of its tier, if it has one
+    pub(crate) fn internal_mint_token(
+        &mut self,
+        my_token_id: u16,
+        receiver_id: &AccountId,
+        //the token's own royalty. Tokens without one follow the collection royalty
+        royalty: Option<HashMap<AccountId, u32>>,
+    ) -> (TokenId, Option<U128>) {
+        //the token ID is always the ID drawn from the pool, so the template, hashes and attributes of that ID apply to it
+        let final_token_id = &format!("{}", my_token_id);
+        /*
+            the tier of the token's artwork is its type and is listed as its class next to the attributes set by the owner.
+            While the seed is committed but not revealed the artwork isn't known, and assign_token_tiers gives the token its tier later.
+        */
+        let tier = self.internal_artwork_id(my_token_id)
+            .and_then(|artwork_id| self.internal_record_tier_mint(artwork_id));
+        let token_type = tier.as_ref().map(|(name, _)| name.clone());
+        let curr_time = env::block_timestamp() / 1_000_000;
+
+        //the rest of the metadata comes from the collection template when the token is viewed
+        let metadata = TokenMetadata {
+            title: None, // ex. "Arch Nemesis: Mail Carrier" or "Parcel #5055"
+            description: None, // free-form description
+            media: None, // URL to associated media, preferably to decentralized, content-addressed storage
+            media_hash: None, // Base64-encoded sha256 hash of content referenced by the `media` field. Required if `media` is included.
+            copies: Some(1), // number of copies of this set of metadata in existence when token was minted.
+            issued_at: Some(curr_time), // When token was issued or minted, Unix epoch in milliseconds
+            expires_at: None, // When token expires, Unix epoch in milliseconds
+            starts_at: None, // When token starts being valid, Unix epoch in milliseconds
+            updated_at: None, // When token was last updated, Unix epoch in milliseconds
+            extra: None, // anything extra the NFT wants to store on-chain. Derived from the attributes when the token is viewed.
+            reference: None, // URL to an off-chain JSON file with more info.
+            reference_hash: None, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
+        };
+
+        //specify the token struct that contains the owner ID 
+        let token = Token {
+            //set the owner ID equal to the receiver ID passed into the function
+            owner_id: receiver_id.clone(),
+            //we set the approved account IDs to the default value (an empty map)
+            approved_account_ids: Default::default(),
+            //the next approval ID is set to 0
+            next_approval_id: 0,
+            //the map of perpetual royalties for the token (The owner will get 100% - total perpetual royalties)
+            royalty,
+            //the type of the token is the name of its tier
+            token_type,
+        };
+
+        //insert the token ID and token struct and make sure that the token doesn't exist
+        assert!(
+            self.tokens_by_id.insert(final_token_id, &token).is_none(),
+            "Token already exists"
+        );
+
+        //insert the token ID and metadata
+        metadata.assert_valid();
+        self.token_metadata_by_id.insert(final_token_id, &metadata);
+
+        //call the internal method for adding the token to the owner
+        self.internal_add_token_to_owner(&token.owner_id, final_token_id);
+
+        //add the token to the index of its type
+        if let Some(token_type) = token.token_type.as_ref() {
+            self.internal_add_token_to_type(token_type, final_token_id);
+        }
+
+        (final_token_id.clone(), tier.and_then(|(_, tier)| tier.price))
+    }
+}
+
+//log a single mint event covering every receiver of the batch
+pub(crate) fn log_nft_mint(mint_logs: Vec<NftMintLog>) {
+    // Construct the mint log as per the events standard.
+    let nft_mint_log: EventLog = EventLog {
+        // Standard name ("nep171").
+        standard: NFT_STANDARD_NAME.to_string(),
+        // Version of the standard ("nft-1.0.0").
+        version: NFT_METADATA_SPEC.to_string(),
+        // The data related with the event stored in a vector.
+        event: EventLogVariant::NftMint(mint_logs),
+    };
+
+    // Log the serialized json.
+    env::log_str(&nft_mint_log.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{accounts, get_created_receipts};
+
+    #[test]
+    fn metadatas_follow_the_minted_ids() {
+        let mut contract = setup_contract(2);
+        contract.nft_airdrop_ids(vec![(accounts(1), vec![5, 6])]);
+        let titles: Vec<String> = contract.get_metadatas(None, None).into_iter().map(|metadata| metadata.title.unwrap()).collect();
+        assert_eq!(titles, vec!["Mr Giggles #5", "Mr Giggles #6"]);
+        assert_eq!(contract.get_metadatas(Some(U128(1)), Some(1))[0].title.as_deref(), Some("Mr Giggles #6"));
+    }
+
+    #[test]
+    fn overpayment_is_refunded() {
+        let mut contract = setup_contract(0);
+        contract.add_sale_phase(SalePhase {
+            name: "public".to_string(),
+            start_time: 0,
+            end_time: None,
+            lists: vec![SaleList::Public],
+            price: U128(ONE_NEAR),
+            max_per_wallet: None,
+            list_limits: HashMap::new(),
+        });
+
+        set_caller(accounts(1), 3 * ONE_NEAR);
+        let initial_storage_usage = env::storage_usage();
+        let result = contract.nft_mint(accounts(1), None, None);
+        let storage_cost = Balance::from(env::storage_usage() - initial_storage_usage) * env::storage_byte_cost();
+
+        //the caller pays the phase price and the storage of the token, and gets the rest back
+        assert_eq!(result.token_ids.len(), 1);
+        assert_eq!(result.price.0, ONE_NEAR);
+        assert_eq!(result.storage_cost.0, storage_cost);
+        assert_eq!(result.charged.0, ONE_NEAR + storage_cost);
+        let refunds: Vec<Balance> = get_created_receipts()
+            .into_iter()
+            .filter(|receipt| receipt.receiver_id == accounts(1))
+            .flat_map(|receipt| receipt.actions)
+            .filter_map(|action| match action {
+                VmAction::Transfer { deposit } => Some(deposit),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(refunds, vec![3 * ONE_NEAR - result.charged.0]);
     }
-}
\ No newline at end of file
+}
@@ -197,15 +197,21 @@ impl NonFungibleTokenCore for Contract {
     fn nft_token(&self, token_id: TokenId) -> Option<JsonToken> {
         //if there is some token ID in the tokens_by_id collection
         if let Some(token) = self.tokens_by_id.get(&token_id) {
-            //we'll get the metadata for that token
-            let metadata = self.token_metadata_by_id.get(&token_id).unwrap();
+            //we'll get the metadata for that token, as it should be shown before or after the reveal
+            let metadata = self.internal_resolve_metadata(
+                &token_id,
+                token.token_type.as_ref(),
+                self.token_metadata_by_id.get(&token_id).unwrap(),
+            );
+            //and the royalty that applies to the token
+            let royalty = self.internal_token_royalty(&token);
             //we return the JsonToken (wrapped by Some since we return an option)
             Some(JsonToken {
                 token_id,
                 owner_id: token.owner_id,
                 metadata,
                 approved_account_ids: token.approved_account_ids,
-                royalty: token.royalty,
+                royalty,
             })
         } else { //if there wasn't a token ID in the tokens_by_id collection, we return None
             None
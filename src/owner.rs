@@ -0,0 +1,133 @@
+use crate::*;
+
+//the current owner of the contract and the account it is being handed over to, if any
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OwnerInfo {
+    pub owner_id: AccountId,
+    pub pending_owner_id: Option<AccountId>,
+}
+
+#[near_bindgen]
+impl Contract {
+    //propose a new owner for the contract. The new owner needs to accept before anything changes
+    #[payable]
+    pub fn propose_owner(&mut self, new_owner: AccountId) {
+        //assert that the user attached exactly 1 yoctoNEAR for security reasons
+        assert_one_yocto();
+        self.assert_owner();
+        assert_ne!(new_owner, self.owner_id, "Account is already the owner");
+
+        self.pending_owner_id = Some(new_owner.clone());
+        log_ownership_event(EventLogVariant::OwnershipTransferProposed(vec![OwnershipTransferLog {
+            old_owner_id: self.owner_id.to_string(),
+            new_owner_id: new_owner.to_string(),
+        }]));
+    }
+
+    //accept the ownership of the contract. Can only be called by the proposed owner
+    #[payable]
+    pub fn accept_ownership(&mut self) {
+        //assert that the user attached exactly 1 yoctoNEAR for security reasons
+        assert_one_yocto();
+        let new_owner = env::predecessor_account_id();
+        assert_eq!(
+            self.pending_owner_id.as_ref(),
+            Some(&new_owner),
+            "Only the proposed owner can accept the ownership"
+        );
+
+        let old_owner = std::mem::replace(&mut self.owner_id, new_owner);
+        self.pending_owner_id = None;
+        log_ownership_event(EventLogVariant::OwnershipTransferAccepted(vec![OwnershipTransferLog {
+            old_owner_id: old_owner.to_string(),
+            new_owner_id: self.owner_id.to_string(),
+        }]));
+    }
+
+    //cancel the pending ownership proposal
+    #[payable]
+    pub fn cancel_ownership_proposal(&mut self) {
+        //assert that the user attached exactly 1 yoctoNEAR for security reasons
+        assert_one_yocto();
+        self.assert_owner();
+        let pending_owner = self.pending_owner_id.take().expect("No pending owner");
+
+        log_ownership_event(EventLogVariant::OwnershipTransferCancelled(vec![OwnershipTransferLog {
+            old_owner_id: self.owner_id.to_string(),
+            new_owner_id: pending_owner.to_string(),
+        }]));
+    }
+
+    //get the current owner of the contract and the proposed owner, if any
+    pub fn get_owner(&self) -> OwnerInfo {
+        OwnerInfo {
+            owner_id: self.owner_id.clone(),
+            pending_owner_id: self.pending_owner_id.clone(),
+        }
+    }
+}
+
+//log a step of the ownership transfer
+fn log_ownership_event(event: EventLogVariant) {
+    // Construct the ownership log.
+    let ownership_log: EventLog = EventLog {
+        // Standard name ("mrgiggles").
+        standard: CONTRACT_STANDARD_NAME.to_string(),
+        // Version of the contract events ("1.0.0").
+        version: CONTRACT_EVENT_VERSION.to_string(),
+        // The data related with the event stored in a vector.
+        event,
+    };
+
+    // Log the serialized json.
+    env::log_str(&ownership_log.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    #[test]
+    fn proposed_owner_takes_over_once_accepted() {
+        let mut contract = setup_contract(0);
+        set_caller(accounts(0), 1);
+        contract.propose_owner(accounts(1));
+        assert_eq!(contract.get_owner().owner_id, accounts(0));
+        assert_eq!(contract.get_owner().pending_owner_id, Some(accounts(1)));
+
+        set_caller(accounts(1), 1);
+        contract.accept_ownership();
+        let owner = contract.get_owner();
+        assert_eq!(owner.owner_id, accounts(1));
+        assert!(owner.pending_owner_id.is_none());
+        assert!(contract.has_role(Role::Admin, accounts(1)));
+        assert!(!contract.has_role(Role::Admin, accounts(0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the proposed owner can accept the ownership")]
+    fn only_the_proposed_owner_can_accept() {
+        let mut contract = setup_contract(0);
+        set_caller(accounts(0), 1);
+        contract.propose_owner(accounts(1));
+
+        set_caller(accounts(2), 1);
+        contract.accept_ownership();
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the proposed owner can accept the ownership")]
+    fn cancelled_proposal_cannot_be_accepted() {
+        let mut contract = setup_contract(0);
+        set_caller(accounts(0), 1);
+        contract.propose_owner(accounts(1));
+        contract.cancel_ownership_proposal();
+        assert!(contract.get_owner().pending_owner_id.is_none());
+
+        set_caller(accounts(1), 1);
+        contract.accept_ownership();
+    }
+}
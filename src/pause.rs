@@ -0,0 +1,128 @@
+use crate::*;
+
+//which parts of the contract are currently paused
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PauseState {
+    pub minting: bool,   // nft_mint and every other way of minting new tokens
+    pub transfers: bool, // nft_transfer, nft_transfer_call and nft_transfer_payout
+    pub approvals: bool, // nft_approve. Revoking approvals stays possible
+}
+
+#[near_bindgen]
+impl Contract {
+    //pause or unpause parts of the contract. Flags that aren't passed in are left as they are
+    pub fn set_pause_state(&mut self, minting: Option<bool>, transfers: Option<bool>, approvals: Option<bool>) {
+        self.assert_role(Role::Pauser);
+        self.paused = PauseState {
+            minting: minting.unwrap_or(self.paused.minting),
+            transfers: transfers.unwrap_or(self.paused.transfers),
+            approvals: approvals.unwrap_or(self.paused.approvals),
+        };
+
+        // Construct the pause log.
+        let pause_log: EventLog = EventLog {
+            // Standard name ("mrgiggles").
+            standard: CONTRACT_STANDARD_NAME.to_string(),
+            // Version of the contract events ("1.0.0").
+            version: CONTRACT_EVENT_VERSION.to_string(),
+            // The data related with the event stored in a vector.
+            event: EventLogVariant::PauseUpdate(vec![PauseUpdateLog {
+                minting: self.paused.minting,
+                transfers: self.paused.transfers,
+                approvals: self.paused.approvals,
+            }]),
+        };
+
+        // Log the serialized json.
+        env::log_str(&pause_log.to_string());
+    }
+
+    //get which parts of the contract are currently paused
+    pub fn get_pause_state(&self) -> PauseState {
+        self.paused
+    }
+}
+
+impl Contract {
+    //make sure minting isn't paused
+    pub(crate) fn assert_minting_not_paused(&self) {
+        assert!(!self.paused.minting, "Minting is paused");
+    }
+
+    //make sure transfers aren't paused
+    pub(crate) fn assert_transfers_not_paused(&self) {
+        assert!(!self.paused.transfers, "Transfers are paused");
+    }
+
+    //make sure approvals aren't paused
+    pub(crate) fn assert_approvals_not_paused(&self) {
+        assert!(!self.paused.approvals, "Approvals are paused");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    //set up a public sale and token 5 owned by accounts(1), with everything paused
+    fn setup_paused() -> Contract {
+        let mut contract = setup_with_tokens(vec![5]);
+        contract.add_sale_phase(SalePhase {
+            name: "public".to_string(),
+            start_time: 0,
+            end_time: None,
+            lists: vec![SaleList::Public],
+            price: U128(ONE_NEAR),
+            max_per_wallet: None,
+            list_limits: HashMap::new(),
+        });
+        contract.set_pause_state(Some(true), Some(true), Some(true));
+        contract
+    }
+
+    #[test]
+    #[should_panic(expected = "Minting is paused")]
+    fn paused_minting_rejects_mints() {
+        let mut contract = setup_paused();
+        set_caller(accounts(2), ONE_NEAR);
+        contract.nft_mint(accounts(2), None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Transfers are paused")]
+    fn paused_transfers_reject_transfers() {
+        let mut contract = setup_paused();
+        set_caller(accounts(1), 1);
+        contract.nft_transfer(accounts(2), "5".to_string(), None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Approvals are paused")]
+    fn paused_approvals_reject_approvals() {
+        let mut contract = setup_paused();
+        set_caller(accounts(1), ONE_NEAR);
+        contract.nft_approve("5".to_string(), accounts(2), None);
+    }
+
+    #[test]
+    fn everything_works_again_once_unpaused() {
+        let mut contract = setup_paused();
+        contract.set_pause_state(Some(false), Some(false), Some(false));
+        assert!(!contract.get_pause_state().minting);
+
+        set_caller(accounts(2), 2 * ONE_NEAR);
+        contract.nft_mint(accounts(2), None, None);
+        assert_eq!(contract.nft_supply_for_owner(accounts(2)).0, 1);
+
+        set_caller(accounts(1), ONE_NEAR);
+        contract.nft_approve("5".to_string(), accounts(3), None);
+        assert!(contract.nft_is_approved("5".to_string(), accounts(3), None));
+
+        set_caller(accounts(1), 1);
+        contract.nft_transfer(accounts(2), "5".to_string(), None, None);
+        assert_eq!(contract.nft_token("5".to_string()).unwrap().owner_id, accounts(2));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::*;
+
+//marks an ID that already left the pool
+const TAKEN: u32 = u32::MAX;
+
+/*
+    the pool of token IDs that haven't been minted yet, laid out as an array of slots where slots 0 to len - 1 hold the remaining IDs.
+    The IDs of the initial range are implied by their slot and are never written to storage. Only the slots that were
+    overwritten, along with the position of the IDs that moved, are stored, so taking an ID out of the pool swaps the
+    last slot into its place and costs the same amount of gas no matter how big the collection is.
+*/
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct TokenPool {
+    //the first ID of the initial range. Slot i holds range_start + i until it is overwritten
+    range_start: u16,
+    //the number of IDs in the initial range
+    range_len: u32,
+    //the number of IDs left in the pool
+    len: u32,
+    //the IDs of the slots that no longer hold their range ID
+    slots: LookupMap<u32, u16>,
+    //the slots of the IDs that aren't in their range slot, or TAKEN once they left the pool
+    positions: LookupMap<u16, u32>,
+}
+
+impl TokenPool {
+    //create an empty pool
+    pub fn empty() -> Self {
+        Self::with_range(1, 0)
+    }
+
+    //create a pool holding every ID from `from` up to and including `to`
+    pub fn with_range(from: u16, to: u16) -> Self {
+        let range_len = if to >= from { (to - from) as u32 + 1 } else { 0 };
+        Self {
+            range_start: from,
+            range_len,
+            len: range_len,
+            slots: LookupMap::new(StorageKey::TokenPoolSlots.try_to_vec().unwrap()),
+            positions: LookupMap::new(StorageKey::TokenPoolPositions.try_to_vec().unwrap()),
+        }
+    }
+
+    //get the number of IDs left in the pool
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    //check if every ID was taken out of the pool
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    //check if the ID is part of the initial range
+    fn in_range(&self, token_id: u16) -> bool {
+        token_id >= self.range_start && ((token_id - self.range_start) as u32) < self.range_len
+    }
+
+    //get the ID held by the given slot
+    pub fn get(&self, slot: u32) -> Option<u16> {
+        if slot >= self.len {
+            return None;
+        }
+        self.slots.get(&slot).or(Some(self.range_start + slot as u16))
+    }
+
+    //get the slot holding the given ID, if it is still in the pool
+    fn position(&self, token_id: u16) -> Option<u32> {
+        match self.positions.get(&token_id) {
+            Some(TAKEN) => None,
+            Some(slot) => Some(slot),
+            None if self.in_range(token_id) => Some((token_id - self.range_start) as u32),
+            None => None,
+        }
+    }
+
+    //check if the ID was ever added to the pool, whether it is still there or not
+    pub fn was_added(&self, token_id: u16) -> bool {
+        self.in_range(token_id) || self.positions.contains_key(&token_id)
+    }
+
+    //add an ID at the end of the pool
+    pub fn push(&mut self, token_id: u16) {
+        self.slots.insert(&self.len, &token_id);
+        self.positions.insert(&token_id, &self.len);
+        self.len += 1;
+    }
+
+    //take the ID out of the given slot, moving the last ID of the pool into its place
+    pub fn swap_remove(&mut self, slot: u32) -> u16 {
+        let token_id = self.get(slot).expect("Slot out of bounds");
+        let last_slot = self.len - 1;
+        if slot != last_slot {
+            let last_token_id = self.get(last_slot).unwrap();
+            self.slots.insert(&slot, &last_token_id);
+            self.positions.insert(&last_token_id, &slot);
+        }
+        self.slots.remove(&last_slot);
+        self.positions.insert(&token_id, &TAKEN);
+        self.len = last_slot;
+        token_id
+    }
+
+    //take the given ID out of the pool, returning None if it isn't there
+    pub fn take(&mut self, token_id: u16) -> Option<u16> {
+        self.position(token_id).map(|slot| self.swap_remove(slot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    #[test]
+    fn every_id_is_taken_once() {
+        set_caller(accounts(0), 0);
+        let mut pool = TokenPool::with_range(5, 9);
+        pool.push(20);
+        pool.push(3);
+        assert_eq!(pool.take(7), Some(7));
+        assert_eq!(pool.take(7), None);
+        assert!(pool.was_added(7));
+
+        let mut taken = vec![7];
+        let mut slot = 0;
+        while !pool.is_empty() {
+            slot = (slot + 3) % pool.len();
+            taken.push(pool.swap_remove(slot));
+        }
+        taken.sort_unstable();
+        assert_eq!(taken, vec![3, 5, 6, 7, 8, 9, 20]);
+        assert_eq!(pool.take(20), None);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::*;
+
+/*
+    keeps track of the randomness used to draw token IDs.
+    Every draw hashes the block's random seed together with a nonce that is incremented on each draw, so
+    several draws in the same block still land on different tokens.
+    In commit-reveal mode the owner commits the sha256 hash of a secret seed before the sale. Once the sale is over
+    the seed is revealed and, together with the random seed of the reveal block, fixes the starting index that maps
+    token IDs onto artwork: token `id` shows artwork `(id - 1 + starting_index) % max_supply + 1`. Tiers, attributes,
+    content hashes and the "{id}" of the metadata template all refer to artwork IDs, so nobody knows which artwork a
+    token gets until the seed is revealed. Anyone can then check the seed against the commitment and recompute the
+    assignment order. Without a committed seed every token shows the artwork with its own ID.
+*/
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Default, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Randomness {
+    pub mint_nonce: u64,                        // number of draws done so far
+    pub seed_commitment: Option<Base64VecU8>,   // sha256 hash of the owner's secret seed
+    pub revealed_seed: Option<Base64VecU8>,     // the secret seed, once revealed
+    pub reveal_random_seed: Option<Base64VecU8>, // the block random seed mixed into the starting index at reveal
+    pub starting_index: Option<u64>,            // offset of the artwork assignment, set at reveal
+    pub tiers_assigned_up_to: u32,              // token ID up to which tokens minted before the reveal got their tier
+}
+
+#[near_bindgen]
+impl Contract {
+    /*
+        commit the sha256 hash of a secret seed. This can only be done before the first token is minted.
+        The tier of a token isn't known when it is minted, so tiers can't have their own price or supply in this mode.
+    */
+    pub fn commit_random_seed(&mut self, seed_hash: Base64VecU8) {
+        self.assert_role(Role::Admin);
+        //airdropped tokens don't draw their ID, but the seed would still change their artwork
+        assert!(
+            !self.internal_minting_started(),
+            "The random seed cannot be committed once minting started"
+        );
+        assert!(
+            self.tiers.values().all(|tier| tier.price.is_none() && tier.supply.is_none()),
+            "Tiers with a price or a supply need the artwork of a token to be known when it is minted"
+        );
+        assert_eq!(seed_hash.0.len(), 32, "Seed hash must be a sha256 hash");
+        self.randomness.seed_commitment = Some(seed_hash);
+    }
+
+    //reveal the committed secret seed and fix the starting index of the artwork assignment
+    pub fn reveal_random_seed(&mut self, seed: Base64VecU8) -> u64 {
+        self.assert_role(Role::Admin);
+        let seed_commitment = self.randomness.seed_commitment.as_ref().expect("No seed committed");
+        assert!(self.randomness.revealed_seed.is_none(), "Seed already revealed");
+        assert_eq!(env::sha256(&seed.0), seed_commitment.0, "Seed does not match the commitment");
+
+        //the total number of tokens in the collection, minted or not
+        let collection_size = self.max_supply as u64;
+        assert!(collection_size > 0, "No tokens to assign");
+        let random_seed = env::random_seed();
+        let starting_index = random_u64(&[seed.0.as_slice(), random_seed.as_slice()].concat()) % collection_size;
+
+        self.randomness.revealed_seed = Some(seed);
+        self.randomness.reveal_random_seed = Some(Base64VecU8(random_seed));
+        self.randomness.starting_index = Some(starting_index);
+        starting_index
+    }
+
+    /*
+        give their tier to the tokens minted before the seed was revealed, going over the next `limit` token IDs.
+        Returns how many token IDs are left to go over.
+    */
+    pub fn assign_token_tiers(&mut self, limit: u32) -> u32 {
+        self.assert_role(Role::Admin);
+        assert!(self.randomness.starting_index.is_some(), "The seed must be revealed first");
+        let from = self.randomness.tiers_assigned_up_to + 1;
+        let to = self.randomness.tiers_assigned_up_to.saturating_add(limit).min(self.max_supply);
+        for my_token_id in from..=to {
+            let token_id = format!("{}", my_token_id);
+            match self.tokens_by_id.get(&token_id) {
+                //tokens minted once the starting index was fixed got their tier when they were minted
+                Some(mut token) if token.token_type.is_none() => {
+                    let artwork_id = self.internal_artwork_id(my_token_id as u16).unwrap();
+                    if let Some((name, _)) = self.internal_record_tier_mint(artwork_id) {
+                        self.internal_add_token_to_type(&name, &token_id);
+                        token.token_type = Some(name);
+                        self.tokens_by_id.insert(&token_id, &token);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.randomness.tiers_assigned_up_to = to;
+        self.max_supply - to
+    }
+
+    //get the ID of the artwork the given token ID shows, once it is known and revealed
+    pub fn get_artwork_id(&self, token_id: u16) -> Option<u16> {
+        if self.internal_artwork_hidden() {
+            return None;
+        }
+        self.internal_artwork_id(token_id)
+    }
+
+    //get the state of the randomness used for the draws
+    pub fn get_randomness(&self) -> Randomness {
+        self.randomness.clone()
+    }
+}
+
+impl Contract {
+    /*
+        draw a random token ID out of the pool of remaining IDs.
+        A draw that lands on an ID of a sold-out tier takes that ID out of the pool for good and draws again,
+        so every redraw brings the pool closer to holding only IDs that can be minted.
+    */
+    pub(crate) fn internal_draw_token_id(&mut self) -> u16 {
+        self.assert_pool_complete();
+        assert!(self.internal_available_count() > 0, "Exceeds max nfts");
+
+        loop {
+            //mix the block random seed with the nonce so each draw gets its own entropy
+            let entropy = [
+                env::random_seed().as_slice(),
+                &self.randomness.mint_nonce.to_le_bytes(),
+            ].concat();
+            self.randomness.mint_nonce += 1;
+
+            let slot = (random_u64(&entropy) % self.token_ids.len() as u64) as u32;
+            if self.internal_tier_available(self.token_ids.get(slot).unwrap()) {
+                return self.token_ids.swap_remove(slot);
+            }
+            self.internal_discard_token_id(slot);
+        }
+    }
+
+    //get the ID of the artwork the given token ID shows. It isn't known until the committed seed is revealed
+    pub(crate) fn internal_artwork_id(&self, token_id: u16) -> Option<u16> {
+        if self.randomness.seed_commitment.is_none() {
+            return Some(token_id);
+        }
+        let starting_index = self.randomness.starting_index?;
+        Some(((token_id as u64 - 1 + starting_index) % self.max_supply as u64) as u16 + 1)
+    }
+
+    //get the token ID that shows the given artwork ID, if it is known yet
+    pub(crate) fn internal_artwork_token_id(&self, artwork_id: u16) -> Option<u16> {
+        if self.randomness.seed_commitment.is_none() {
+            return Some(artwork_id);
+        }
+        let starting_index = self.randomness.starting_index?;
+        let max_supply = self.max_supply as u64;
+        Some(((artwork_id as u64 - 1 + max_supply - starting_index) % max_supply) as u16 + 1)
+    }
+
+    //get the artwork ID of the given token as a key of the artwork data, if it is known yet
+    pub(crate) fn internal_artwork_key(&self, token_id: &TokenId) -> Option<TokenId> {
+        let my_token_id = token_id.parse().ok()?;
+        self.internal_artwork_id(my_token_id).map(|artwork_id| format!("{}", artwork_id))
+    }
+
+    //get the token ID that shows the artwork with the given key, if it is known yet
+    pub(crate) fn internal_artwork_token_key(&self, artwork_id: &TokenId) -> Option<TokenId> {
+        let artwork_id = artwork_id.parse().ok()?;
+        self.internal_artwork_token_id(artwork_id).map(|token_id| format!("{}", token_id))
+    }
+
+    //check if the artwork of the tokens is unknown because the committed seed wasn't revealed yet
+    pub(crate) fn internal_artwork_pending(&self) -> bool {
+        self.randomness.seed_commitment.is_some() && self.randomness.starting_index.is_none()
+    }
+
+    //check if some tokens may not have their tier yet because they were minted before the seed was revealed
+    pub(crate) fn internal_tiers_pending(&self) -> bool {
+        self.randomness.seed_commitment.is_some() && self.randomness.tiers_assigned_up_to < self.max_supply
+    }
+
+    //take a specific token ID out of the pool of remaining IDs
+    pub(crate) fn internal_take_token_id(&mut self, token_id: u16) -> u16 {
+        self.assert_pool_complete();
+        self.token_ids.take(token_id)
+            .unwrap_or_else(|| env::panic_str(&format!("Token {} is not available", token_id)))
+    }
+}
+
+//hash the given bytes and read the first 8 bytes of the hash as a number
+pub(crate) fn random_u64(entropy: &[u8]) -> u64 {
+    let hash = env::sha256(entropy);
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    const SEED: [u8; 32] = [3; 32];
+
+    //tokens 5 and 6 are minted before the seed is revealed
+    fn setup() -> Contract {
+        let mut contract = setup_contract(2);
+        contract.commit_random_seed(Base64VecU8(env::sha256(&SEED)));
+        contract.nft_airdrop_ids(vec![(accounts(1), vec![5, 6])]);
+        contract
+    }
+
+    #[test]
+    fn artwork_is_assigned_through_the_starting_index() {
+        let mut contract = setup();
+        assert_eq!(contract.get_artwork_id(5), None);
+        assert_eq!(contract.get_token_tier(5), None);
+        assert!(contract.nft_token("5".to_string()).unwrap().metadata.media.is_none());
+
+        let starting_index = contract.reveal_random_seed(Base64VecU8(SEED.to_vec()));
+        let artwork_id = ((5 - 1 + starting_index) % 666) as u16 + 1;
+        assert_eq!(contract.get_artwork_id(5), Some(artwork_id));
+        assert_eq!(contract.internal_artwork_token_id(artwork_id), Some(5));
+        let metadata = contract.nft_token("5".to_string()).unwrap().metadata;
+        assert_eq!(metadata.title.unwrap(), format!("Mr Giggles #{}", artwork_id));
+
+        //the tokens minted before the seed was revealed get the tier of their artwork
+        let tier = contract.internal_token_tier(artwork_id).map(|(name, _)| name).unwrap();
+        assert_eq!(contract.assign_token_tiers(1_000), 0);
+        assert_eq!(contract.get_token_tier(5), Some(tier.clone()));
+        assert_eq!(contract.get_token_attributes("5".to_string())[0].value, tier);
+        assert!(contract.nft_tokens_for_type(tier, None, None).iter().any(|token| token.token_id == "5"));
+    }
+
+    #[test]
+    #[should_panic(expected = "The random seed cannot be committed once minting started")]
+    fn seed_is_committed_before_airdrops() {
+        let mut contract = setup_contract(1);
+        contract.nft_airdrop_ids(vec![(accounts(1), vec![5])]);
+        contract.commit_random_seed(Base64VecU8(env::sha256(&SEED)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Tiers with a price or a supply need the artwork of a token to be known when it is minted")]
+    fn tier_supplies_need_a_known_artwork() {
+        let mut contract = setup_contract(0);
+        contract.commit_random_seed(Base64VecU8(env::sha256(&SEED)));
+        contract.set_tier("gold".to_string(), Tier {
+            ranges: vec![(1, 120)],
+            price: None,
+            supply: Some(10),
+            ..Default::default()
+        });
+    }
+}
@@ -0,0 +1,263 @@
+use crate::*;
+
+/*
+    the delayed reveal of the artwork. While a placeholder is set and the artwork isn't revealed, every token shows the
+    placeholder and its class and attributes are hidden, so the rarity of a token can't be told when it lands. The artwork
+    is assigned through the starting index of the committed seed, so the token IDs listed by mint events give nothing away.
+    The provenance hash (the sha256 hash of the ordered artwork) is committed before the sale so holders can check the
+    artwork wasn't swapped.
+*/
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Reveal {
+    pub provenance_hash: Option<Base64VecU8>,    // sha256 hash of the artwork, committed before the first mint
+    pub placeholder_media: Option<String>,       // media shown by every token until the reveal. None turns the reveal mode off
+    pub placeholder_media_hash: Option<Base64VecU8>, // sha256 hash of the placeholder media
+    pub placeholder_reference: Option<String>,   // reference shown by every token until the reveal
+    pub placeholder_reference_hash: Option<Base64VecU8>, // sha256 hash of the placeholder reference
+    pub media_hashes_uploaded: u32,              // number of artwork IDs with the hash of their media
+    pub reference_hashes_uploaded: u32,          // number of artwork IDs with the hash of their reference
+    pub revealed_at: Option<u64>,                // when the artwork was revealed, Unix epoch in milliseconds
+}
+
+impl Reveal {
+    //check if the tokens show the placeholder until the artwork is revealed
+    pub fn is_enabled(&self) -> bool {
+        self.placeholder_media.is_some()
+    }
+
+    //check if the tokens still show the placeholder
+    pub fn is_hidden(&self) -> bool {
+        self.is_enabled() && self.revealed_at.is_none()
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /*
+        turn the reveal mode on by setting the placeholder every token shows until the reveal. This can only be done before
+        the first token is minted, once the random seed assigning the artwork after the sale is committed
+    */
+    pub fn set_placeholder_metadata(
+        &mut self,
+        media: String,
+        media_hash: Base64VecU8,
+        reference: Option<String>,
+        reference_hash: Option<Base64VecU8>,
+    ) {
+        self.assert_role(Role::Admin);
+        assert!(
+            !self.internal_minting_started(),
+            "The placeholder cannot be changed once minting started"
+        );
+        assert!(
+            self.randomness.seed_commitment.is_some(),
+            "The random seed must be committed before the placeholder is set"
+        );
+        assert!(
+            self.tiers.values().all(|tier| tier.price.is_none() && tier.supply.is_none()),
+            "Tiers with a price or a supply would give the class of a token away when it is minted"
+        );
+        assert!(
+            reference.is_none() || reference_hash.is_some(),
+            "Reference hash is required when reference is set"
+        );
+        for hash in std::iter::once(&media_hash).chain(reference_hash.iter()) {
+            assert_eq!(hash.0.len(), 32, "Content hashes must be sha256 hashes");
+        }
+        self.reveal.placeholder_media = Some(media);
+        self.reveal.placeholder_media_hash = Some(media_hash);
+        self.reveal.placeholder_reference = reference;
+        self.reveal.placeholder_reference_hash = reference_hash;
+    }
+
+    //commit to the artwork before the sale. This can only be done before the first token is minted
+    pub fn commit_provenance_hash(&mut self, provenance_hash: Base64VecU8) {
+        self.assert_role(Role::Admin);
+        assert!(
+            !self.internal_minting_started(),
+            "The provenance hash cannot be changed once minting started"
+        );
+        assert_eq!(provenance_hash.0.len(), 32, "Provenance hash must be a sha256 hash");
+        self.reveal.provenance_hash = Some(provenance_hash);
+    }
+
+    /*
+        reveal the artwork of every token, minted or not, by pointing the metadata template to where it lives, ex. "<CID>/{id}.gif".
+        This can only be done once, after the committed seed was revealed, the tokens minted before got their tier and the
+        content hashes of every artwork were uploaded, so no token shows media without the hash to check it against
+    */
+    pub fn reveal_metadata(&mut self, media: String, reference: Option<String>) {
+        self.assert_role(Role::Admin);
+        assert!(self.reveal.is_enabled(), "Reveal mode is off");
+        assert!(self.reveal.revealed_at.is_none(), "Metadata was already revealed");
+        assert!(
+            self.reveal.provenance_hash.is_some(),
+            "The provenance hash must be committed before the reveal"
+        );
+        assert!(!self.internal_artwork_pending(), "The seed must be revealed before the artwork");
+        assert!(
+            !self.internal_tiers_pending(),
+            "Every token must get its tier with assign_token_tiers before the artwork is revealed"
+        );
+        assert_eq!(
+            self.reveal.media_hashes_uploaded, self.max_supply,
+            "Every artwork needs its media hash before the reveal, {} of {} were uploaded",
+            self.reveal.media_hashes_uploaded, self.max_supply
+        );
+        if reference.is_some() {
+            assert_eq!(
+                self.reveal.reference_hashes_uploaded, self.max_supply,
+                "Every artwork needs its reference hash before the reference is revealed, {} of {} were uploaded",
+                self.reveal.reference_hashes_uploaded, self.max_supply
+            );
+        }
+
+        self.metadata_template.media = Some(media);
+        self.metadata_template.reference = reference;
+        self.reveal.revealed_at = Some(env::block_timestamp() / 1_000_000);
+
+        //let indexers know the metadata of every minted token changed
+        log_all_metadata_updated("reveal");
+    }
+
+    //get the state of the reveal
+    pub fn get_reveal(&self) -> Reveal {
+        self.reveal.clone()
+    }
+}
+
+impl Contract {
+    //check if the artwork of the tokens, and so their class, can't be shown yet
+    pub(crate) fn internal_artwork_hidden(&self) -> bool {
+        self.reveal.is_hidden() || self.internal_artwork_pending()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::{accounts, get_logs};
+
+    const SEED: [u8; 32] = [3; 32];
+
+    fn setup() -> Contract {
+        let mut contract = setup_contract(2);
+        contract.commit_random_seed(Base64VecU8(env::sha256(&SEED)));
+        contract.set_placeholder_metadata("https://example.com/hidden.gif".to_string(), Base64VecU8(vec![1; 32]), None, None);
+        contract.commit_provenance_hash(Base64VecU8(vec![7; 32]));
+        contract
+    }
+
+    //upload the hashes of every artwork, a few at a time to stay within the gas limit
+    fn upload_content_hashes(contract: &mut Contract) {
+        for artwork_ids in (1..=666u16).collect::<Vec<_>>().chunks(100) {
+            set_caller(accounts(0), 0);
+            contract.set_content_hashes(artwork_ids.iter().map(|artwork_id| (artwork_id.to_string(), ContentHashes {
+                media_hash: Base64VecU8(vec![2; 32]),
+                reference_hash: Some(Base64VecU8(vec![3; 32])),
+            })).collect());
+        }
+        set_caller(accounts(0), 0);
+    }
+
+    //check that nothing tells the class of token 5 away
+    fn assert_class_hidden(contract: &Contract) {
+        assert!(contract.get_token_attributes("5".to_string()).is_empty());
+        assert_eq!(contract.get_token_tier(5), None);
+        assert_eq!(contract.get_artwork_id(5), None);
+        for tier in contract.get_tiers() {
+            assert!(contract.nft_tokens_for_type(tier.name.clone(), None, None).is_empty());
+            assert!(contract.nft_tokens_for_trait("Class".to_string(), tier.name.clone(), None, None).is_empty());
+            assert_eq!(contract.nft_supply_for_type(tier.name).0, 0);
+            assert_eq!(tier.tier.minted, 0);
+        }
+    }
+
+    #[test]
+    fn tokens_show_the_placeholder_until_the_reveal() {
+        let mut contract = setup();
+        contract.nft_airdrop_ids(vec![(accounts(1), vec![5])]);
+        let metadata = contract.nft_token("5".to_string()).unwrap().metadata;
+        assert_eq!(metadata.media.unwrap(), "https://example.com/hidden.gif");
+        assert_eq!(metadata.media_hash.unwrap().0, vec![1; 32]);
+        assert!(metadata.extra.is_none());
+        assert_class_hidden(&contract);
+
+        //the artwork is known once the seed is revealed, but stays hidden until the reveal
+        contract.reveal_random_seed(Base64VecU8(SEED.to_vec()));
+        assert_eq!(contract.assign_token_tiers(1_000), 0);
+        let artwork_id = contract.internal_artwork_id(5).unwrap();
+        contract.set_token_attributes(HashMap::from([(
+            artwork_id.to_string(),
+            vec![attribute("Hat", "Crown")],
+        )]));
+        assert!(contract.nft_token("5".to_string()).unwrap().metadata.extra.is_none());
+        assert!(contract.nft_tokens_for_trait("Hat".to_string(), "Crown".to_string(), None, None).is_empty());
+        assert_class_hidden(&contract);
+
+        upload_content_hashes(&mut contract);
+        contract.reveal_metadata("QmMedia/{id}.gif".to_string(), Some("QmReference/{id}.json".to_string()));
+        let metadata = contract.nft_token("5".to_string()).unwrap().metadata;
+        assert_eq!(metadata.media.unwrap(), format!("QmMedia/{}.gif", artwork_id));
+        assert_eq!(metadata.media_hash.unwrap().0, vec![2; 32]);
+        assert_eq!(metadata.reference.unwrap(), format!("QmReference/{}.json", artwork_id));
+        assert_eq!(metadata.reference_hash.unwrap().0, vec![3; 32]);
+        let tier = contract.internal_token_tier(artwork_id).map(|(name, _)| name).unwrap();
+        assert!(metadata.extra.unwrap().contains("Crown"));
+        assert_eq!(contract.get_token_tier(5), Some(tier.clone()));
+        let attributes = contract.get_token_attributes("5".to_string());
+        assert_eq!((attributes[0].value.as_str(), attributes[1].value.as_str()), (tier.as_str(), "Crown"));
+        assert_eq!(contract.nft_tokens_for_trait("Hat".to_string(), "Crown".to_string(), None, None)[0].token_id, "5");
+        assert!(get_logs().last().unwrap().ends_with(r#""event":"nft_metadata_update","data":[{"token_ids":[],"memo":"reveal"}]}"#));
+    }
+
+    #[test]
+    #[should_panic(expected = "Attributes cannot be changed once the artwork is revealed")]
+    fn attributes_are_locked_by_the_reveal() {
+        let mut contract = setup();
+        contract.reveal_random_seed(Base64VecU8(SEED.to_vec()));
+        contract.assign_token_tiers(1_000);
+        upload_content_hashes(&mut contract);
+        contract.reveal_metadata("QmMedia/{id}.gif".to_string(), None);
+        contract.set_token_attributes(HashMap::from([("5".to_string(), vec![attribute("Hat", "Crown")])]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Every artwork needs its media hash before the reveal, 1 of 666 were uploaded")]
+    fn artwork_is_revealed_with_its_hashes() {
+        let mut contract = setup();
+        contract.reveal_random_seed(Base64VecU8(SEED.to_vec()));
+        contract.assign_token_tiers(1_000);
+        //uploading the hashes of an artwork twice counts it once
+        for _ in 0..2 {
+            contract.set_content_hashes(HashMap::from([("01".to_string(), ContentHashes {
+                media_hash: Base64VecU8(vec![2; 32]),
+                reference_hash: None,
+            })]));
+        }
+        contract.reveal_metadata("QmMedia/{id}.gif".to_string(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Reference hash is required when reference is set")]
+    fn placeholder_needs_its_hashes() {
+        let mut contract = setup_contract(0);
+        contract.commit_random_seed(Base64VecU8(env::sha256(&SEED)));
+        contract.set_placeholder_metadata(
+            "https://example.com/hidden.gif".to_string(),
+            Base64VecU8(vec![1; 32]),
+            Some("https://example.com/hidden.json".to_string()),
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The provenance hash cannot be changed once minting started")]
+    fn provenance_is_committed_before_the_sale() {
+        let mut contract = setup();
+        contract.nft_airdrop(vec![(accounts(1), 1)]);
+        contract.commit_provenance_hash(Base64VecU8(vec![8; 32]));
+    }
+}
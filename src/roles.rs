@@ -0,0 +1,178 @@
+use crate::*;
+
+//the roles that can be handed out for the privileged methods. The owner implicitly holds every role
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,          // manages roles, the sale schedule and the randomness. Implies every other role
+    ListManager,    // manages the OG list and the whitelist
+    Pauser,         // pauses and unpauses the contract
+    RoyaltyManager, // manages the collection and token royalties
+    Minter,         // airdrops tokens out of the reserve
+}
+
+impl Role {
+    //the name of the role as it appears in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::ListManager => "list_manager",
+            Role::Pauser => "pauser",
+            Role::RoyaltyManager => "royalty_manager",
+            Role::Minter => "minter",
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    //grant a role to an account
+    pub fn grant_role(&mut self, role: Role, account_id: AccountId) {
+        self.assert_role(Role::Admin);
+        let mut roles = self.roles.get(&account_id).unwrap_or_default();
+        assert!(!roles.contains(&role), "Account already has the role");
+        roles.push(role);
+        self.roles.insert(&account_id, &roles);
+
+        log_role_event(EventLogVariant::RoleGranted(vec![RoleLog {
+            role: role.as_str().to_string(),
+            account_id: account_id.to_string(),
+            sender_id: env::predecessor_account_id().to_string(),
+        }]));
+    }
+
+    //revoke a role from an account
+    pub fn revoke_role(&mut self, role: Role, account_id: AccountId) {
+        self.assert_role(Role::Admin);
+        self.internal_remove_role(role, &account_id);
+    }
+
+    //give up a role held by the caller
+    pub fn renounce_role(&mut self, role: Role) {
+        self.internal_remove_role(role, &env::predecessor_account_id());
+    }
+
+    //check if an account holds a role, either directly or through being the owner or an admin
+    pub fn has_role(&self, role: Role, account_id: AccountId) -> bool {
+        self.internal_has_role(role, &account_id)
+    }
+
+    //get the roles granted to an account
+    pub fn get_roles(&self, account_id: AccountId) -> Vec<Role> {
+        self.roles.get(&account_id).unwrap_or_default()
+    }
+}
+
+impl Contract {
+    //check if an account holds a role, either directly or through being the owner or an admin
+    pub(crate) fn internal_has_role(&self, role: Role, account_id: &AccountId) -> bool {
+        if account_id == &self.owner_id {
+            return true;
+        }
+        let roles = self.roles.get(account_id).unwrap_or_default();
+        roles.contains(&role) || roles.contains(&Role::Admin)
+    }
+
+    //make sure the caller holds a role
+    pub(crate) fn assert_role(&self, role: Role) {
+        assert!(
+            self.internal_has_role(role, &env::predecessor_account_id()),
+            "Requires the {} role",
+            role.as_str()
+        );
+    }
+
+    //remove a role from an account
+    pub(crate) fn internal_remove_role(&mut self, role: Role, account_id: &AccountId) {
+        let mut roles = self.roles.get(account_id).unwrap_or_default();
+        let position = roles.iter().position(|granted| *granted == role).expect("Account doesn't have the role");
+        roles.remove(position);
+        if roles.is_empty() {
+            self.roles.remove(account_id);
+        } else {
+            self.roles.insert(account_id, &roles);
+        }
+
+        log_role_event(EventLogVariant::RoleRevoked(vec![RoleLog {
+            role: role.as_str().to_string(),
+            account_id: account_id.to_string(),
+            sender_id: env::predecessor_account_id().to_string(),
+        }]));
+    }
+}
+
+//log a change to the roles
+fn log_role_event(event: EventLogVariant) {
+    // Construct the role log.
+    let role_log: EventLog = EventLog {
+        // Standard name ("mrgiggles").
+        standard: CONTRACT_STANDARD_NAME.to_string(),
+        // Version of the contract events ("1.0.0").
+        version: CONTRACT_EVENT_VERSION.to_string(),
+        // The data related with the event stored in a vector.
+        event,
+    };
+
+    // Log the serialized json.
+    env::log_str(&role_log.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::{accounts, get_logs};
+
+    #[test]
+    fn roles_are_granted_and_revoked() {
+        let mut contract = setup_contract(0);
+        contract.grant_role(Role::Pauser, accounts(1));
+        assert!(get_logs()[0].contains(r#""event":"role_granted","data":[{"role":"pauser","account_id":"bob","sender_id":"alice"}]"#));
+        assert!(contract.has_role(Role::Pauser, accounts(1)));
+        assert!(!contract.has_role(Role::Minter, accounts(1)));
+        assert_eq!(contract.get_roles(accounts(1)).len(), 1);
+
+        contract.revoke_role(Role::Pauser, accounts(1));
+        assert!(!contract.has_role(Role::Pauser, accounts(1)));
+        assert!(contract.get_roles(accounts(1)).is_empty());
+    }
+
+    #[test]
+    fn roles_can_be_renounced() {
+        let mut contract = setup_contract(0);
+        contract.grant_role(Role::ListManager, accounts(1));
+        set_caller(accounts(1), 0);
+        contract.renounce_role(Role::ListManager);
+        assert!(!contract.has_role(Role::ListManager, accounts(1)));
+    }
+
+    #[test]
+    fn owner_and_admins_hold_every_role() {
+        let mut contract = setup_contract(0);
+        contract.grant_role(Role::Admin, accounts(1));
+        for role in [Role::Admin, Role::ListManager, Role::Pauser, Role::RoyaltyManager, Role::Minter] {
+            assert!(contract.has_role(role, accounts(0)));
+            assert!(contract.has_role(role, accounts(1)));
+            assert!(!contract.has_role(role, accounts(2)));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires the admin role")]
+    fn roles_are_granted_by_admins() {
+        let mut contract = setup_contract(0);
+        contract.grant_role(Role::Pauser, accounts(1));
+        set_caller(accounts(1), 0);
+        contract.grant_role(Role::Pauser, accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires the pauser role")]
+    fn privileged_methods_need_their_role() {
+        let mut contract = setup_contract(0);
+        contract.grant_role(Role::ListManager, accounts(1));
+        set_caller(accounts(1), 0);
+        contract.set_pause_state(Some(true), None, None);
+    }
+}
@@ -1,6 +1,9 @@
 use crate::*;
 
-pub trait NonFungibleTokenCore {
+//maximum number of perpetual royalty receivers on a token. Together with the token owner this keeps payouts small enough to fit in GAS
+pub const MAX_ROYALTY_RECEIVERS: usize = 6;
+
+pub trait NonFungibleTokenPayout {
     //calculates the payout for a token given the passed in balance. This is a view method
     fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: u32) -> Payout;
     
@@ -17,46 +20,16 @@ pub trait NonFungibleTokenCore {
 }
 
 #[near_bindgen]
-impl NonFungibleTokenCore for Contract {
+impl NonFungibleTokenPayout for Contract {
 
     //calculates the payout for a token given the passed in balance. This is a view method
     fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: u32) -> Payout {
         //get the token object
 		let token = self.tokens_by_id.get(&token_id).expect("No token");
 
-        //get the owner of the token
-        let owner_id = token.owner_id;
-        //keep track of the total perpetual royalties
-        let mut total_perpetual = 0;
-        //get the u128 version of the passed in balance (which was U128 before)
-        let balance_u128 = u128::from(balance);
-		//keep track of the payout object to send back
-        let mut payout_object = Payout {
-            payout: HashMap::new()
-        };
-        //get the royalty object from token
-		let royalty = token.royalty;
-
-        //make sure we're not paying out to too many people (GAS limits this)
-		assert!(royalty.len() as u32 <= max_len_payout, "Market cannot payout to that many receivers");
-
-        //go through each key and value in the royalty object
-		for (k, v) in royalty.iter() {
-            //get the key
-			let key = k.clone();
-            //only insert into the payout if the key isn't the token owner (we add their payout at the end)
-			if key != owner_id {
-                //
-				payout_object.payout.insert(key, royalty_to_payout(*v, balance_u128));
-				total_perpetual += *v;
-			}
-		}
-
-		// payout to previous owner who gets 100% - total perpetual royalties
-		payout_object.payout.insert(owner_id, royalty_to_payout(10000 - total_perpetual, balance_u128));
-
-        //return the payout object
-		payout_object
+        //get the royalty that applies to the token and compute the payout for it
+        let royalty = self.internal_token_royalty(&token);
+        internal_payout(&token.owner_id, &royalty, balance, max_len_payout)
 	}
 
     //transfers the token to the receiver ID and returns the payout object that should be payed given the passed in balance. 
@@ -89,38 +62,258 @@ impl NonFungibleTokenCore for Contract {
             &previous_token.approved_account_ids,
         );
 
-        //get the owner of the token
-        let owner_id = previous_token.owner_id;
-        //keep track of the total perpetual royalties
-        let mut total_perpetual = 0;
-        //get the u128 version of the passed in balance (which was U128 before)
-        let balance_u128 = u128::from(balance);
-		//keep track of the payout object to send back
-        let mut payout_object = Payout {
-            payout: HashMap::new()
-        };
-        //get the royalty object from token
-		let royalty = previous_token.royalty;
-
-        //make sure we're not paying out to too many people (GAS limits this)
-		assert!(royalty.len() as u32 <= max_len_payout, "Market cannot payout to that many receivers");
-
-        //go through each key and value in the royalty object
-		for (k, v) in royalty.iter() {
-            //get the key
-			let key = k.clone();
-            //only insert into the payout if the key isn't the token owner (we add their payout at the end)
-			if key != owner_id {
-                //
-				payout_object.payout.insert(key, royalty_to_payout(*v, balance_u128));
-				total_perpetual += *v;
-			}
-		}
-
-		// payout to previous owner who gets 100% - total perpetual royalties
-		payout_object.payout.insert(owner_id, royalty_to_payout(10000 - total_perpetual, balance_u128));
-
-        //return the payout object
-		payout_object
-    }
-}
\ No newline at end of file
+        //get the royalty that applies to the token and compute the payout for the previous owner
+        let royalty = self.internal_token_royalty(&previous_token);
+        internal_payout(&previous_token.owner_id, &royalty, balance, max_len_payout)
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    //replace the collection royalty that every token without its own royalty pays out
+    pub fn set_default_royalty(&mut self, royalty: HashMap<AccountId, u32>) {
+        self.assert_role(Role::RoyaltyManager);
+        assert_valid_royalty(&royalty);
+        self.default_royalty = royalty;
+        log_royalty_update(None, Some(&self.default_royalty));
+    }
+
+    //add a receiver to the collection royalty or change their percentage
+    pub fn set_default_royalty_receiver(&mut self, account_id: AccountId, amount: u32) {
+        self.assert_role(Role::RoyaltyManager);
+        let mut royalty = self.default_royalty.clone();
+        royalty.insert(account_id, amount);
+        assert_valid_royalty(&royalty);
+        self.default_royalty = royalty;
+        log_royalty_update(None, Some(&self.default_royalty));
+    }
+
+    //remove a receiver from the collection royalty
+    pub fn remove_default_royalty_receiver(&mut self, account_id: AccountId) {
+        self.assert_role(Role::RoyaltyManager);
+        assert!(
+            self.default_royalty.remove(&account_id).is_some(),
+            "Account is not a royalty receiver"
+        );
+        log_royalty_update(None, Some(&self.default_royalty));
+    }
+
+    //get the collection royalty that every token without its own royalty pays out
+    pub fn get_default_royalty(&self) -> HashMap<AccountId, u32> {
+        self.default_royalty.clone()
+    }
+
+    //set a royalty for a single token that takes precedence over the collection royalty. Passing None removes it
+    pub fn set_token_royalty(&mut self, token_id: TokenId, royalty: Option<HashMap<AccountId, u32>>) {
+        self.assert_role(Role::RoyaltyManager);
+        let mut token = self.tokens_by_id.get(&token_id).expect("No token");
+        if let Some(royalty) = royalty.as_ref() {
+            assert_valid_royalty(royalty);
+        }
+        token.royalty = royalty;
+        self.tokens_by_id.insert(&token_id, &token);
+        log_royalty_update(Some(vec![token_id]), token.royalty.as_ref());
+    }
+}
+
+impl Contract {
+    //get the royalty that applies to the token: its own royalty if it has one, otherwise the collection royalty
+    pub(crate) fn internal_token_royalty(&self, token: &Token) -> HashMap<AccountId, u32> {
+        token.royalty.clone().unwrap_or_else(|| self.default_royalty.clone())
+    }
+}
+
+//calculate the payout of a sale given the owner of the token and the royalty that applies to it
+pub(crate) fn internal_payout(
+    owner_id: &AccountId,
+    royalty: &HashMap<AccountId, u32>,
+    balance: U128,
+    max_len_payout: u32,
+) -> Payout {
+    //keep track of the total perpetual royalties
+    let mut total_perpetual = 0;
+    //get the u128 version of the passed in balance (which was U128 before)
+    let balance_u128 = u128::from(balance);
+    //keep track of the payout object to send back
+    let mut payout_object = Payout {
+        payout: HashMap::new()
+    };
+
+    //make sure we're not paying out to too many people (GAS limits this). The owner is paid out on top of the royalty receivers
+    let receivers = royalty.keys().filter(|account_id| *account_id != owner_id).count() + 1;
+    assert!(receivers as u32 <= max_len_payout, "Market cannot payout to that many receivers");
+
+    //go through each key and value in the royalty object
+    for (k, v) in royalty.iter() {
+        //only insert into the payout if the key isn't the token owner (we add their payout at the end)
+        if k != owner_id {
+            payout_object.payout.insert(k.clone(), royalty_to_payout(*v, balance_u128));
+            total_perpetual += *v;
+        }
+    }
+
+    // payout to previous owner who gets 100% - total perpetual royalties
+    payout_object.payout.insert(owner_id.clone(), royalty_to_payout(10000 - total_perpetual, balance_u128));
+
+    //return the payout object
+    payout_object
+}
+
+//log that the collection royalty (no token IDs) or the royalty of some tokens was updated
+fn log_royalty_update(token_ids: Option<Vec<TokenId>>, royalty: Option<&HashMap<AccountId, u32>>) {
+    // Construct the royalty update log.
+    let royalty_update_log: EventLog = EventLog {
+        // Standard name ("mrgiggles").
+        standard: CONTRACT_STANDARD_NAME.to_string(),
+        // Version of the contract events ("1.0.0").
+        version: CONTRACT_EVENT_VERSION.to_string(),
+        // The data related with the event stored in a vector.
+        event: EventLogVariant::RoyaltyUpdate(vec![RoyaltyUpdateLog {
+            // The tokens the royalty applies to. None for the collection royalty.
+            token_ids,
+            // The new royalty. None if the token royalty was removed.
+            royalty: royalty.map(|royalty| {
+                royalty.iter()
+                    .map(|(account_id, amount)| (account_id.to_string(), *amount))
+                    .collect()
+            }),
+        }]),
+    };
+
+    // Log the serialized json.
+    env::log_str(&royalty_update_log.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    fn account(account_id: &str) -> AccountId {
+        account_id.parse().unwrap()
+    }
+
+    //set up a contract with an open public sale at 1 NEAR per token
+    fn setup_sale() -> Contract {
+        let mut contract = setup_contract(0);
+        contract.add_sale_phase(SalePhase {
+            name: "public".to_string(),
+            start_time: 0,
+            end_time: None,
+            lists: vec![SaleList::Public],
+            price: U128(ONE_NEAR),
+            max_per_wallet: None,
+            list_limits: HashMap::new(),
+        });
+        contract
+    }
+
+    #[test]
+    fn payout_uses_default_royalty() {
+        let mut contract = setup_sale();
+        set_caller(account("user1.near"), 2 * ONE_NEAR);
+        let token_id = contract.nft_mint(account("user1.near"), None, None).token_ids.remove(0);
+
+        let payout = contract.nft_payout(token_id, U128(10 * ONE_NEAR), 2).payout;
+        assert_eq!(payout.len(), 2);
+        assert_eq!(payout[&account("mrgiggles.near")], U128(7 * ONE_NEAR / 10));
+        assert_eq!(payout[&account("user1.near")], U128(93 * ONE_NEAR / 10));
+    }
+
+    #[test]
+    fn payout_uses_owner_royalty_override() {
+        let mut contract = setup_sale();
+        set_caller(accounts(0), 2 * ONE_NEAR);
+        let mut royalty = HashMap::new();
+        royalty.insert(account("artist.near"), 1000);
+        royalty.insert(account("dao.near"), 500);
+        let token_id = contract.nft_mint(account("user1.near"), Some(royalty), None).token_ids.remove(0);
+
+        let payout = contract.nft_payout(token_id, U128(10 * ONE_NEAR), 3).payout;
+        assert_eq!(payout.len(), 3);
+        assert_eq!(payout[&account("artist.near")], U128(ONE_NEAR));
+        assert_eq!(payout[&account("dao.near")], U128(ONE_NEAR / 2));
+        assert_eq!(payout[&account("user1.near")], U128(85 * ONE_NEAR / 10));
+    }
+
+    #[test]
+    fn default_royalty_update_applies_to_minted_tokens() {
+        let mut contract = setup_sale();
+        set_caller(account("user1.near"), 2 * ONE_NEAR);
+        let token_id = contract.nft_mint(account("user1.near"), None, None).token_ids.remove(0);
+
+        set_caller(accounts(0), 0);
+        let mut royalty = HashMap::new();
+        royalty.insert(account("dao.near"), 250);
+        contract.set_default_royalty(royalty);
+
+        let payout = contract.nft_payout(token_id, U128(100), 2).payout;
+        assert_eq!(payout.len(), 2);
+        assert_eq!(payout[&account("dao.near")], U128(2));
+        assert_eq!(payout[&account("user1.near")], U128(97));
+    }
+
+    #[test]
+    fn token_royalty_takes_precedence() {
+        let mut contract = setup_sale();
+        set_caller(account("user1.near"), 2 * ONE_NEAR);
+        let token_id = contract.nft_mint(account("user1.near"), None, None).token_ids.remove(0);
+
+        set_caller(accounts(0), 0);
+        let mut royalty = HashMap::new();
+        royalty.insert(account("artist.near"), 1000);
+        contract.set_token_royalty(token_id.clone(), Some(royalty));
+        contract.set_default_royalty_receiver(account("dao.near"), 300);
+
+        let payout = contract.nft_payout(token_id.clone(), U128(100), 2).payout;
+        assert_eq!(payout[&account("artist.near")], U128(10));
+        assert_eq!(payout[&account("user1.near")], U128(90));
+
+        //removing the token royalty falls back to the collection royalty
+        contract.set_token_royalty(token_id.clone(), None);
+        let payout = contract.nft_payout(token_id, U128(100), 3).payout;
+        assert_eq!(payout[&account("mrgiggles.near")], U128(7));
+        assert_eq!(payout[&account("dao.near")], U128(3));
+        assert_eq!(payout[&account("user1.near")], U128(90));
+    }
+
+    #[test]
+    #[should_panic(expected = "Market cannot payout to that many receivers")]
+    fn payout_counts_token_owner() {
+        let mut contract = setup_sale();
+        set_caller(account("user1.near"), 2 * ONE_NEAR);
+        let token_id = contract.nft_mint(account("user1.near"), None, None).token_ids.remove(0);
+        contract.nft_payout(token_id, U128(100), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires the royalty_manager role")]
+    fn only_royalty_managers_can_override_royalty() {
+        let mut contract = setup_sale();
+        set_caller(account("user1.near"), 2 * ONE_NEAR);
+        let mut royalty = HashMap::new();
+        royalty.insert(account("user1.near"), 5000);
+        contract.nft_mint(account("user1.near"), Some(royalty), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Perpetual royalties cannot add up to more than 10000 basis points")]
+    fn royalty_cannot_exceed_total() {
+        let mut contract = setup_sale();
+        let mut royalty = HashMap::new();
+        royalty.insert(account("artist.near"), 6000);
+        royalty.insert(account("dao.near"), 4001);
+        contract.set_default_royalty(royalty);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot add more than 6 perpetual royalty amounts")]
+    fn royalty_receivers_are_capped() {
+        let mut contract = setup_sale();
+        let royalty = (0..7)
+            .map(|index| (account(&format!("receiver{}.near", index)), 100))
+            .collect();
+        contract.set_default_royalty(royalty);
+    }
+}
@@ -0,0 +1,312 @@
+use crate::*;
+
+//the lists an account can qualify through for a sale phase
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum SaleList {
+    Og,
+    Whitelist,
+    Public,
+}
+
+//a single phase of the sale schedule
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SalePhase {
+    pub name: String,              // ex. "presale" or "public"
+    pub start_time: u64,           // When the phase opens, Unix epoch in milliseconds
+    pub end_time: Option<u64>,     // When the phase closes, Unix epoch in milliseconds. None keeps it open until closed
+    pub lists: Vec<SaleList>,      // lists whose members are allowed to mint during the phase
+    pub price: U128,               // price per token in yoctoNEAR
+    pub max_per_wallet: Option<u32>, // maximum number of tokens a single account can mint during the phase
+    #[serde(default)]
+    pub list_limits: HashMap<SaleList, u32>, // per list overrides of max_per_wallet, ex. {"og": 3, "whitelist": 2}
+}
+
+impl SalePhase {
+    //check if the phase is open at the given time (in milliseconds)
+    pub fn is_open(&self, time: u64) -> bool {
+        time >= self.start_time && !matches!(self.end_time, Some(end_time) if time >= end_time)
+    }
+
+    //get the maximum number of tokens an account qualifying through the given list can mint (None means no limit)
+    pub fn max_mints_for(&self, list: SaleList) -> Option<u32> {
+        self.list_limits.get(&list).copied().or(self.max_per_wallet)
+    }
+
+    //check if anyone is allowed to mint during the phase
+    pub fn is_public(&self) -> bool {
+        self.lists.contains(&SaleList::Public)
+    }
+}
+
+//The Json sale phase is what will be returned from view calls.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonSalePhase {
+    //index of the phase in the schedule
+    pub phase_id: u64,
+    //the phase itself
+    #[serde(flatten)]
+    pub phase: SalePhase,
+}
+
+//the full sale schedule along with the phase that is currently open
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SaleSchedule {
+    pub phases: Vec<JsonSalePhase>,
+    pub active_phase: Option<JsonSalePhase>,
+}
+
+#[near_bindgen]
+impl Contract {
+    //append a new phase to the sale schedule and return its ID
+    pub fn add_sale_phase(&mut self, phase: SalePhase) -> u64 {
+        self.assert_role(Role::Admin);
+        assert_valid_sale_phase(&phase);
+        self.sale_phases.push(&phase);
+        self.sale_phases.len() - 1
+    }
+
+    //replace an existing phase of the sale schedule
+    pub fn update_sale_phase(&mut self, phase_id: u64, phase: SalePhase) {
+        self.assert_role(Role::Admin);
+        assert!(phase_id < self.sale_phases.len(), "No sale phase");
+        assert_valid_sale_phase(&phase);
+        self.sale_phases.replace(phase_id, &phase);
+    }
+
+    //close a phase of the sale schedule right away
+    pub fn close_sale_phase(&mut self, phase_id: u64) {
+        self.assert_role(Role::Admin);
+        let mut phase = self.sale_phases.get(phase_id).expect("No sale phase");
+        let curr_time = env::block_timestamp() / 1_000_000;
+        assert!(
+            !matches!(phase.end_time, Some(end_time) if end_time <= curr_time),
+            "Sale phase already closed"
+        );
+        phase.end_time = Some(curr_time.max(phase.start_time));
+        self.sale_phases.replace(phase_id, &phase);
+    }
+
+    //get how many more tokens the given account can mint right now, optionally proving it is part of a list's Merkle tree
+    pub fn get_mint_allowance(&self, account_id: AccountId, merkle_proof: Option<Vec<Base64VecU8>>) -> u32 {
+        let curr_time = env::block_timestamp() / 1_000_000;
+        //the account can never mint more than what is left in the pool outside of the reserve
+        let remaining = self.internal_sale_available_count();
+        //overlapping phases each add their own allowance
+        self.internal_eligible_phases(&account_id, merkle_proof.as_deref(), curr_time)
+            .into_iter()
+            .map(|(phase_id, phase, list)| {
+                self.internal_phase_allowance(phase_id, &phase, list, &account_id).unwrap_or(remaining)
+            })
+            .fold(0, u32::saturating_add)
+            .min(remaining)
+    }
+
+    //get the full sale schedule and the phase that is currently open
+    pub fn get_sale_schedule(&self) -> SaleSchedule {
+        let curr_time = env::block_timestamp() / 1_000_000;
+        SaleSchedule {
+            phases: self.sale_phases.iter()
+                .enumerate()
+                .map(|(phase_id, phase)| JsonSalePhase { phase_id: phase_id as u64, phase })
+                .collect(),
+            active_phase: self.internal_active_phase(curr_time)
+                .map(|(phase_id, phase)| JsonSalePhase { phase_id, phase }),
+        }
+    }
+}
+
+impl Contract {
+    /*
+        get the lists the given account is a member of, in order of precedence.
+        An account is a member either because it was added to the list or because the proof shows it is in the list's Merkle tree.
+    */
+    pub(crate) fn internal_account_lists(
+        &self,
+        account_id: &AccountId,
+        merkle_proof: Option<&[Base64VecU8]>,
+    ) -> Vec<SaleList> {
+        let in_merkle_tree = |list: SaleList| {
+            match (self.merkle_roots.get(&list), merkle_proof) {
+                (Some(root), Some(merkle_proof)) => verify_merkle_proof(root, account_id, merkle_proof),
+                _ => false,
+            }
+        };
+
+        let mut lists = Vec::new();
+        if self.oglist.contains_key(account_id) || in_merkle_tree(SaleList::Og) {
+            lists.push(SaleList::Og);
+        }
+        if self.whitelist.contains_key(account_id) || in_merkle_tree(SaleList::Whitelist) {
+            lists.push(SaleList::Whitelist);
+        }
+        //everyone is a member of the public list
+        lists.push(SaleList::Public);
+        lists
+    }
+
+    //get the first phase of the schedule that is open at the given time
+    pub(crate) fn internal_active_phase(&self, time: u64) -> Option<(u64, SalePhase)> {
+        self.sale_phases.iter()
+            .enumerate()
+            .find(|(_, phase)| phase.is_open(time))
+            .map(|(phase_id, phase)| (phase_id as u64, phase))
+    }
+
+    //get every open phase the given account is allowed to mint in along with the list it qualifies through
+    pub(crate) fn internal_eligible_phases(
+        &self,
+        account_id: &AccountId,
+        merkle_proof: Option<&[Base64VecU8]>,
+        time: u64,
+    ) -> Vec<(u64, SalePhase, SaleList)> {
+        let account_lists = self.internal_account_lists(account_id, merkle_proof);
+        self.sale_phases.iter()
+            .enumerate()
+            .filter(|(_, phase)| phase.is_open(time))
+            .filter_map(|(phase_id, phase)| {
+                account_lists.iter()
+                    .find(|list| phase.lists.contains(list))
+                    .copied()
+                    .map(|list| (phase_id as u64, phase, list))
+            })
+            .collect()
+    }
+
+    //get how many more tokens the account can mint in the given phase (None means no limit)
+    pub(crate) fn internal_phase_allowance(
+        &self,
+        phase_id: u64,
+        phase: &SalePhase,
+        list: SaleList,
+        account_id: &AccountId,
+    ) -> Option<u32> {
+        let minted = self.phase_minted.get(&(phase_id, account_id.clone())).unwrap_or(0);
+        phase.max_mints_for(list).map(|max_mints| max_mints.saturating_sub(minted))
+    }
+
+    /*
+        split the given quantity between the eligible phases of an account. Overlapping phases each add their own
+        allowance, and the phases are filled in schedule order.
+    */
+    pub(crate) fn internal_split_between_phases(
+        &self,
+        eligible_phases: Vec<(u64, SalePhase, SaleList)>,
+        account_id: &AccountId,
+        quantity: u32,
+    ) -> Vec<(u64, SalePhase, u32)> {
+        let mut left = quantity;
+        let mut split = Vec::new();
+        for (phase_id, phase, list) in eligible_phases {
+            if left == 0 {
+                break;
+            }
+            let phase_quantity = self.internal_phase_allowance(phase_id, &phase, list, account_id)
+                .map_or(left, |allowance| allowance.min(left));
+            if phase_quantity > 0 {
+                left -= phase_quantity;
+                split.push((phase_id, phase, phase_quantity));
+            }
+        }
+        assert!(
+            left == 0,
+            "Mint limit reached: {} left across the open sale phases",
+            quantity - left
+        );
+        split
+    }
+}
+
+//make sure a sale phase passed in by the owner is well formed
+fn assert_valid_sale_phase(phase: &SalePhase) {
+    assert!(!phase.lists.is_empty(), "Sale phase must have at least one list");
+    if let Some(end_time) = phase.end_time {
+        assert!(end_time > phase.start_time, "Sale phase must end after it starts");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    fn phase(name: &str, start_time: u64, end_time: Option<u64>, lists: Vec<SaleList>, max_per_wallet: Option<u32>) -> SalePhase {
+        SalePhase {
+            name: name.to_string(),
+            start_time,
+            end_time,
+            lists,
+            price: U128(0),
+            max_per_wallet,
+            list_limits: HashMap::new(),
+        }
+    }
+
+    //accounts(1) is on the OG list and accounts(2) on the whitelist
+    fn setup(phases: Vec<SalePhase>) -> Contract {
+        let mut contract = setup_contract(0);
+        contract.add_oglist(accounts(1));
+        contract.add_whitelist(accounts(2));
+        for phase in phases {
+            contract.add_sale_phase(phase);
+        }
+        contract
+    }
+
+    #[test]
+    #[should_panic(expected = "Mint limit reached: 0 left across the open sale phases")]
+    fn wallet_cap_is_enforced() {
+        let mut contract = setup(vec![phase("public", 0, None, vec![SaleList::Public], Some(2))]);
+        set_caller_at(accounts(3), ONE_NEAR, 10);
+        assert_eq!(contract.get_mint_allowance(accounts(3), None), 2);
+        contract.nft_mint_many(accounts(3), 2, None);
+        assert_eq!(contract.get_mint_allowance(accounts(3), None), 0);
+        contract.nft_mint(accounts(3), None, None);
+    }
+
+    #[test]
+    fn list_limits_follow_the_phase() {
+        let mut presale = phase("presale", 0, Some(100), vec![SaleList::Og, SaleList::Whitelist], Some(1));
+        presale.list_limits.insert(SaleList::Og, 3);
+        let mut contract = setup(vec![presale, phase("public", 100, None, vec![SaleList::Public], Some(2))]);
+
+        set_caller_at(accounts(1), ONE_NEAR, 10);
+        assert_eq!(contract.get_mint_allowance(accounts(1), None), 3);
+        assert_eq!(contract.get_mint_allowance(accounts(2), None), 1);
+        assert_eq!(contract.get_mint_allowance(accounts(3), None), 0);
+        contract.nft_mint_many(accounts(1), 3, None);
+        assert_eq!(contract.get_mint_allowance(accounts(1), None), 0);
+
+        //what was minted during the presale doesn't count against the public sale limit
+        set_caller_at(accounts(1), ONE_NEAR, 100);
+        assert_eq!(contract.get_mint_allowance(accounts(1), None), 2);
+        assert_eq!(contract.get_mint_allowance(accounts(2), None), 2);
+        contract.nft_mint_many(accounts(1), 2, None);
+        assert_eq!(contract.get_mint_allowance(accounts(1), None), 0);
+        assert_eq!(contract.presale_minted.get(&accounts(1)), Some(3));
+        assert_eq!(contract.pubsale_minted.get(&accounts(1)), Some(2));
+    }
+
+    #[test]
+    fn overlapping_phases_add_up() {
+        let mut contract = setup(vec![
+            phase("og", 0, None, vec![SaleList::Og], Some(2)),
+            phase("public", 0, None, vec![SaleList::Public], Some(1)),
+        ]);
+
+        set_caller_at(accounts(1), ONE_NEAR, 10);
+        assert_eq!(contract.get_mint_allowance(accounts(1), None), 3);
+        assert_eq!(contract.get_mint_allowance(accounts(3), None), 1);
+
+        //the earliest phase of the schedule is filled first
+        assert_eq!(contract.nft_mint_many(accounts(1), 3, None).token_ids.len(), 3);
+        assert_eq!(contract.phase_minted.get(&(0, accounts(1))), Some(2));
+        assert_eq!(contract.phase_minted.get(&(1, accounts(1))), Some(1));
+        assert_eq!(contract.get_mint_allowance(accounts(1), None), 0);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::*;
+
+//the token IDs the pool is filled with when the contract is initialized
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum TokenIdPool {
+    //every ID from `from` up to and including `to`
+    Range { from: u16, to: u16 },
+    //an explicit, possibly pre-shuffled, list of IDs. The rest can be appended with add_token_ids
+    List(Vec<u16>),
+}
+
+#[near_bindgen]
+impl Contract {
+    //append token IDs to the pool. Large collections can be loaded this way in several chunks
+    pub fn add_token_ids(&mut self, token_ids: Vec<u16>) {
+        self.assert_role(Role::Admin);
+        self.internal_add_token_ids(token_ids);
+    }
+
+    //get the maximum number of tokens the collection can have
+    pub fn get_max_supply(&self) -> u32 {
+        self.max_supply
+    }
+
+    //get the number of tokens that can still be minted during the sale, keeping the unused reserve aside
+    pub fn get_remaining_count(&self) -> u32 {
+        self.internal_sale_available_count()
+    }
+}
+
+impl Contract {
+    //make sure the given ID is one of the collection's IDs, which go from 1 up to the max supply
+    pub(crate) fn assert_in_collection(&self, token_id: u16) {
+        assert!(
+            token_id >= 1 && token_id as u32 <= self.max_supply,
+            "Token {} is outside of the collection, IDs go from 1 to {}",
+            token_id,
+            self.max_supply
+        );
+    }
+
+    //make sure every ID of the collection is in the pool, so the tiers know how many of their IDs can be drawn
+    pub(crate) fn assert_pool_complete(&self) {
+        assert_eq!(
+            self.token_ids_added,
+            self.max_supply,
+            "Every token ID must be added to the pool before minting"
+        );
+    }
+
+    //get the number of tokens that can still be minted: the IDs left in the pool, except the ones of sold-out tiers
+    pub(crate) fn internal_available_count(&self) -> u32 {
+        self.token_ids.len() - self.internal_stranded_count()
+    }
+
+    //add the given IDs to the pool, making sure they are unique and stay within the max supply
+    pub(crate) fn internal_add_token_ids(&mut self, token_ids: Vec<u16>) {
+        let token_ids_added = self.token_ids_added + token_ids.len() as u32;
+        assert!(
+            token_ids_added <= self.max_supply,
+            "Exceeds the max supply of {}",
+            self.max_supply
+        );
+
+        for token_id in token_ids {
+            self.assert_in_collection(token_id);
+            assert!(
+                !self.token_ids.was_added(token_id),
+                "Token {} was already added",
+                token_id
+            );
+            self.token_ids.push(token_id);
+        }
+        self.token_ids_added = token_ids_added;
+    }
+
+    //check if a token was already taken out of the pool
+    pub(crate) fn internal_minting_started(&self) -> bool {
+        self.token_ids.len() < self.token_ids_added
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    #[test]
+    fn pool_is_loaded_in_chunks() {
+        let mut contract = setup_collection(4, TokenIdPool::List(vec![3, 1]));
+        contract.add_token_ids(vec![4, 2]);
+        assert_eq!(contract.get_remaining_ids(None, None), vec![3, 1, 4, 2]);
+        assert_eq!(contract.get_remaining_count(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Exceeds the max supply of 3")]
+    fn pool_cannot_exceed_max_supply() {
+        let mut contract = setup_collection(3, TokenIdPool::Range { from: 1, to: 3 });
+        contract.add_token_ids(vec![4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token 5 is outside of the collection, IDs go from 1 to 4")]
+    fn pool_ids_stay_within_the_collection() {
+        let mut contract = setup_collection(4, TokenIdPool::List(vec![1]));
+        contract.add_token_ids(vec![5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Every token ID must be added to the pool before minting")]
+    fn pool_is_complete_before_minting() {
+        let mut contract = setup_collection(4, TokenIdPool::List(vec![3, 1]));
+        contract.set_reserve_size(1);
+        contract.nft_airdrop(vec![(accounts(1), 1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token 1 was already added")]
+    fn pool_ids_are_unique() {
+        let mut contract = setup_collection(3, TokenIdPool::List(vec![1]));
+        contract.add_token_ids(vec![2, 1]);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::*;
+
+//the placeholder replaced by the artwork ID of a token in the fields of the template
+pub const TOKEN_ID_PLACEHOLDER: &str = "{id}";
+
+/*
+    the metadata shared by every token of the collection. The metadata of a token is derived from it when the token is viewed,
+    so only the fields a token overrides are stored. "{id}" is replaced by the ID of the artwork the token shows in every field,
+    which is the token ID unless the artwork is assigned through a committed seed. Like the standard allows, media and
+    reference can be paths relative to the `base_uri` of the contract metadata.
+*/
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MetadataTemplate {
+    pub title: Option<String>,       // ex. "Mr Giggles #{id}"
+    pub description: Option<String>, // free-form description shared by every token
+    pub media: Option<String>,       // ex. "<CID>/{id}.gif"
+    pub reference: Option<String>,   // ex. "<CID>/{id}.json"
+}
+
+impl MetadataTemplate {
+    //fill the fields of the metadata that the token doesn't override
+    pub fn apply(&self, artwork_id: &TokenId, metadata: &mut TokenMetadata) {
+        let fill = |field: &mut Option<String>, pattern: &Option<String>| {
+            if field.is_none() {
+                *field = pattern.as_ref().map(|pattern| pattern.replace(TOKEN_ID_PLACEHOLDER, artwork_id));
+            }
+        };
+        fill(&mut metadata.title, &self.title);
+        fill(&mut metadata.description, &self.description);
+        fill(&mut metadata.media, &self.media);
+        fill(&mut metadata.reference, &self.reference);
+    }
+}
+
+//the sha256 hashes of an artwork, uploaded in bulk before the reveal
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContentHashes {
+    pub media_hash: Base64VecU8,             // hash of the media the template points the token to
+    pub reference_hash: Option<Base64VecU8>, // hash of the reference the template points the token to
+}
+
+#[near_bindgen]
+impl Contract {
+    //change the metadata shared by every token, including the ones already minted unless their metadata is frozen
+    pub fn set_metadata_template(&mut self, template: MetadataTemplate) {
+        self.assert_role(Role::Admin);
+        assert!(!self.metadata_frozen, "Metadata is frozen");
+        self.metadata_template = template;
+        log_all_metadata_updated("template");
+    }
+
+    //get the metadata shared by every token
+    pub fn get_metadata_template(&self) -> MetadataTemplate {
+        self.metadata_template.clone()
+    }
+
+    //upload the hashes of the given artwork IDs, whether the tokens showing them were minted or not. This can only be done before the reveal
+    pub fn set_content_hashes(&mut self, hashes: HashMap<TokenId, ContentHashes>) {
+        self.assert_role(Role::Admin);
+        assert!(
+            self.reveal.revealed_at.is_none(),
+            "Content hashes cannot be changed once the artwork is revealed"
+        );
+        for (token_id, content_hashes) in hashes {
+            //the keys are counted to know when every artwork has its hashes, so they must be artwork IDs of the collection
+            let artwork_id = token_id.parse().unwrap_or(0);
+            self.assert_in_collection(artwork_id);
+            let token_id = artwork_id.to_string();
+            self.assert_artwork_not_frozen(&token_id);
+            for hash in std::iter::once(&content_hashes.media_hash).chain(content_hashes.reference_hash.iter()) {
+                assert_eq!(hash.0.len(), 32, "Content hashes must be sha256 hashes");
+            }
+            match self.content_hashes_by_id.insert(&token_id, &content_hashes) {
+                Some(previous) => {
+                    if previous.reference_hash.is_some() {
+                        self.reveal.reference_hashes_uploaded -= 1;
+                    }
+                }
+                None => self.reveal.media_hashes_uploaded += 1,
+            }
+            if content_hashes.reference_hash.is_some() {
+                self.reveal.reference_hashes_uploaded += 1;
+            }
+        }
+    }
+}
+
+impl Contract {
+    /*
+        get the metadata of a token of the given type as it should be shown: its overrides on top of the template filled with
+        the ID of the artwork it shows, or the placeholder until the artwork is revealed
+    */
+    pub(crate) fn internal_resolve_metadata(
+        &self,
+        token_id: &TokenId,
+        token_type: Option<&TokenType>,
+        mut metadata: TokenMetadata,
+    ) -> TokenMetadata {
+        let artwork_id = match self.internal_artwork_key(token_id) {
+            Some(artwork_id) if !self.reveal.is_hidden() => artwork_id,
+            //until then the template is filled with the token ID and every token shows the placeholder
+            _ => {
+                self.metadata_template.apply(token_id, &mut metadata);
+                metadata.media = self.reveal.placeholder_media.clone();
+                metadata.media_hash = self.reveal.placeholder_media_hash.clone();
+                metadata.reference = self.reveal.placeholder_reference.clone();
+                metadata.reference_hash = self.reveal.placeholder_reference_hash.clone();
+                //the attributes would give the rarity of the token away
+                metadata.extra = None;
+                return metadata;
+            }
+        };
+
+        self.metadata_template.apply(&artwork_id, &mut metadata);
+        if let Some(content_hashes) = self.content_hashes_by_id.get(&artwork_id) {
+            metadata.media_hash = metadata.media_hash.or(Some(content_hashes.media_hash));
+            metadata.reference_hash = metadata.reference_hash.or(content_hashes.reference_hash);
+        }
+        //the attributes of the artwork are listed in `extra`, unless the token overrides it
+        metadata.extra = metadata.extra.or_else(|| self.internal_token_extra(&artwork_id, token_type));
+        metadata
+    }
+}
+
+/*
+    log that the metadata of every token changed. Listing every token ID would go over the log size limit and cost more
+    gas as the collection grows, so a single event is logged without any token ID and the memo tells indexers why
+*/
+pub(crate) fn log_all_metadata_updated(memo: &str) {
+    log_nft_metadata_update(vec![], Some(memo.to_string()));
+}
+
+//log that the metadata of the given tokens changed
+pub(crate) fn log_nft_metadata_update(token_ids: Vec<TokenId>, memo: Option<String>) {
+    // Construct the metadata update log as per the events standard.
+    let nft_metadata_update_log: EventLog = EventLog {
+        // Standard name ("nep171").
+        standard: NFT_STANDARD_NAME.to_string(),
+        // Version of the standard that added the event ("1.1.0").
+        version: NFT_UPDATE_EVENT_VERSION.to_string(),
+        // The data related with the event stored in a vector.
+        event: EventLogVariant::NftMetadataUpdate(vec![NftMetadataUpdateLog { token_ids, memo }]),
+    };
+
+    // Log the serialized json.
+    env::log_str(&nft_metadata_update_log.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::{accounts, get_logs};
+
+    #[test]
+    fn metadata_is_derived_from_the_template() {
+        let mut contract = setup_with_tokens(vec![5]);
+        let metadata = contract.nft_token("5".to_string()).unwrap().metadata;
+        assert_eq!(metadata.title.unwrap(), "Mr Giggles #5");
+        assert_eq!(metadata.media.unwrap(), "QmXXsDoynSQpinPRSqu6RGJwSHkkSFA1k2xX7r8kco557P/5.gif");
+        assert_eq!(contract.nft_metadata().base_uri.unwrap(), "https://ipfs.io/ipfs");
+
+        contract.set_metadata_template(MetadataTemplate {
+            title: Some("Giggle {id}".to_string()),
+            ..contract.get_metadata_template()
+        });
+        assert_eq!(contract.nft_token("5".to_string()).unwrap().metadata.title.unwrap(), "Giggle 5");
+        assert!(get_logs().last().unwrap().ends_with(r#""event":"nft_metadata_update","data":[{"token_ids":[],"memo":"template"}]}"#));
+    }
+
+    #[test]
+    fn stored_fields_override_the_template() {
+        let mut contract = setup_with_tokens(vec![5]);
+        let mut metadata = contract.token_metadata_by_id.get(&"5".to_string()).unwrap();
+        assert!(metadata.description.is_none());
+        metadata.media = Some("ipfs://custom.gif".to_string());
+        contract.token_metadata_by_id.insert(&"5".to_string(), &metadata);
+
+        let metadata = contract.nft_token("5".to_string()).unwrap().metadata;
+        assert_eq!(metadata.media.unwrap(), "ipfs://custom.gif");
+        assert!(metadata.description.unwrap().starts_with("Mr Giggles is an art NFT project"));
+    }
+
+    #[test]
+    fn content_hashes_are_uploaded_in_bulk() {
+        let mut contract = setup_contract(1);
+        contract.set_content_hashes(HashMap::from([("5".to_string(), ContentHashes {
+            media_hash: Base64VecU8(vec![1; 32]),
+            reference_hash: Some(Base64VecU8(vec![2; 32])),
+        })]));
+        contract.nft_airdrop_ids(vec![(accounts(1), vec![5])]);
+
+        let metadata = contract.nft_token("5".to_string()).unwrap().metadata;
+        assert_eq!(metadata.media_hash.unwrap().0, vec![1; 32]);
+        assert_eq!(metadata.reference_hash.unwrap().0, vec![2; 32]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Content hashes must be sha256 hashes")]
+    fn content_hashes_are_sha256_hashes() {
+        let mut contract = setup_contract(1);
+        contract.set_content_hashes(HashMap::from([("5".to_string(), ContentHashes {
+            media_hash: Base64VecU8(b"This is media hash.".to_vec()),
+            reference_hash: None,
+        })]));
+    }
+}
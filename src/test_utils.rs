@@ -0,0 +1,62 @@
+use crate::*;
+use near_sdk::test_utils::{accounts, VMContextBuilder};
+use near_sdk::testing_env;
+
+pub(crate) const ONE_NEAR: Balance = 1_000_000_000_000_000_000_000_000;
+
+//make the given account the caller, attaching the given deposit
+pub(crate) fn set_caller(account_id: AccountId, attached_deposit: Balance) {
+    set_caller_at(account_id, attached_deposit, 0);
+}
+
+//make the given account the caller at the given time (in milliseconds), attaching the given deposit
+pub(crate) fn set_caller_at(account_id: AccountId, attached_deposit: Balance, time: u64) {
+    let mut builder = VMContextBuilder::new();
+    builder
+        .predecessor_account_id(account_id)
+        .attached_deposit(attached_deposit)
+        .block_timestamp(time * 1_000_000)
+        .random_seed(vec![1; 32]);
+    testing_env!(builder.build());
+}
+
+//set up a contract with the default metadata, owned by accounts(0), keeping `reserve` tokens for airdrops
+pub(crate) fn setup_contract(reserve: u32) -> Contract {
+    set_caller(accounts(0), 0);
+    let mut contract = Contract::new_default_meta(accounts(0));
+    if reserve > 0 {
+        contract.set_reserve_size(reserve);
+    }
+    contract
+}
+
+//set up a contract with a bare collection of the given size, owned by accounts(0)
+pub(crate) fn setup_collection(max_supply: u32, token_ids: TokenIdPool) -> Contract {
+    set_caller(accounts(0), 0);
+    let metadata = NFTContractMetadata {
+        spec: NFT_METADATA_SPEC.to_string(),
+        name: "Test".to_string(),
+        symbol: "TEST".to_string(),
+        icon: None,
+        base_uri: None,
+        reference: None,
+        reference_hash: None,
+    };
+    Contract::new(accounts(0), metadata, max_supply, token_ids)
+}
+
+//set up a contract with the given token IDs airdropped to accounts(1)
+pub(crate) fn setup_with_tokens(token_ids: Vec<u16>) -> Contract {
+    let mut contract = setup_contract(token_ids.len() as u32);
+    contract.nft_airdrop_ids(vec![(accounts(1), token_ids)]);
+    contract
+}
+
+//build an attribute without a display type
+pub(crate) fn attribute(trait_type: &str, value: &str) -> TokenAttribute {
+    TokenAttribute {
+        trait_type: trait_type.to_string(),
+        value: value.to_string(),
+        display_type: None,
+    }
+}
@@ -0,0 +1,374 @@
+use crate::*;
+
+//a tier of the collection, ex. "gold", along with the artwork IDs that belong to it
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Tier {
+    #[serde(default)]
+    pub ranges: Vec<(u16, u16)>, // ranges of artwork IDs in the tier, both ends included, ex. [[1, 120]]
+    pub price: Option<U128>,     // price per token in yoctoNEAR. None uses the price of the sale phase
+    pub supply: Option<u32>,     // maximum number of tokens of the tier that can be minted. None means no limit
+    #[serde(default, skip_deserializing)]
+    pub token_count: u32, // number of token IDs added to the tier one by one with add_tier_token_ids
+    #[serde(default, skip_deserializing)]
+    pub overridden: u32, // number of token IDs in the tier's ranges that were added to another tier one by one
+    #[serde(default, skip_deserializing)]
+    pub minted: u32, // number of tokens of the tier minted so far
+    #[serde(default, skip_deserializing)]
+    pub discarded: u32, // number of token IDs of the tier taken out of the pool without being minted once it sold out
+}
+
+impl Tier {
+    //check if the ID falls in one of the tier's ranges
+    pub fn in_ranges(&self, token_id: u16) -> bool {
+        self.ranges.iter().any(|(from, to)| token_id >= *from && token_id <= *to)
+    }
+
+    //get the number of token IDs in the tier
+    pub fn size(&self) -> u32 {
+        self.ranges.iter().map(|(from, to)| (to - from) as u32 + 1).sum::<u32>() + self.token_count - self.overridden
+    }
+
+    //get the number of tokens of the tier that can still be minted
+    pub fn remaining(&self) -> u32 {
+        self.supply
+            .map_or(self.size(), |supply| supply.min(self.size()))
+            .saturating_sub(self.minted)
+    }
+
+    //check if no more tokens of the tier can be minted
+    pub fn is_sold_out(&self) -> bool {
+        matches!(self.supply, Some(supply) if self.minted >= supply)
+    }
+
+    //get the number of the tier's token IDs still in the pool that can't be minted because the tier is sold out
+    pub fn stranded(&self) -> u32 {
+        if !self.is_sold_out() {
+            return 0;
+        }
+        self.size().saturating_sub(self.minted + self.discarded)
+    }
+}
+
+//The Json tier is what will be returned from view calls.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonTier {
+    //name of the tier
+    pub name: String,
+    //the tier itself
+    #[serde(flatten)]
+    pub tier: Tier,
+    //number of tokens of the tier that can still be minted
+    pub remaining: u32,
+}
+
+#[near_bindgen]
+impl Contract {
+    //create or replace a tier. Token IDs added one by one are kept. This can only be done before the first token is minted
+    pub fn set_tier(&mut self, name: String, tier: Tier) {
+        self.assert_role(Role::Admin);
+        self.assert_tiers_not_locked();
+        if tier.price.is_some() || tier.supply.is_some() {
+            assert!(
+                !self.internal_artwork_pending(),
+                "Tiers with a price or a supply need the artwork of a token to be known when it is minted"
+            );
+            assert!(
+                !self.reveal.is_enabled(),
+                "Tiers with a price or a supply would give the class of a token away when it is minted"
+            );
+        }
+        for (from, to) in tier.ranges.iter() {
+            assert!(from <= to, "Tier range must start before it ends");
+            self.assert_in_collection(*from);
+            self.assert_in_collection(*to);
+        }
+        //make sure the ranges of the tier don't overlap each other, since their IDs would count twice towards its size
+        for (index, (from, to)) in tier.ranges.iter().enumerate() {
+            assert!(
+                !tier.ranges[index + 1..].iter().any(|(other_from, other_to)| from <= other_to && other_from <= to),
+                "Tier ranges overlap each other"
+            );
+        }
+        //make sure the ranges don't overlap with the ones of another tier
+        for (other_name, other_tier) in self.tiers.iter() {
+            if other_name != name {
+                assert!(
+                    !tier.ranges.iter().any(|(from, to)| {
+                        other_tier.ranges.iter().any(|(other_from, other_to)| from <= other_to && other_from <= to)
+                    }),
+                    "Tier ranges overlap with the {} tier",
+                    other_name
+                );
+            }
+        }
+
+        /*
+            IDs added one by one take precedence over ranges and are left out of the size of the tier whose range they fall in.
+            That count can only be kept up to date if ranges are set before IDs are added one by one.
+        */
+        let existing = self.tiers.get(&name).unwrap_or_default();
+        let overridden = if tier.ranges == existing.ranges { existing.overridden } else { 0 };
+        if tier.ranges != existing.ranges && !tier.ranges.is_empty() {
+            assert!(
+                self.tiers.iter().all(|(_, other_tier)| other_tier.token_count == 0),
+                "Remove the token IDs added one by one before changing tier ranges"
+            );
+        }
+        self.tiers.insert(&name, &Tier {
+            token_count: existing.token_count,
+            overridden,
+            minted: 0,
+            discarded: 0,
+            ..tier
+        });
+    }
+
+    //remove a tier. Its token IDs added one by one need to be removed first
+    pub fn remove_tier(&mut self, name: String) {
+        self.assert_role(Role::Admin);
+        self.assert_tiers_not_locked();
+        let tier = self.tiers.get(&name).expect("No tier");
+        assert_eq!(tier.token_count, 0, "Remove the token IDs of the tier first");
+        self.tiers.remove(&name);
+    }
+
+    //add token IDs outside of the tier's ranges to the tier. These take precedence over the ranges of other tiers
+    pub fn add_tier_token_ids(&mut self, name: String, token_ids: Vec<u16>) {
+        self.assert_role(Role::Admin);
+        self.assert_tiers_not_locked();
+        let mut tier = self.tiers.get(&name).expect("No tier");
+        for token_id in token_ids {
+            self.assert_in_collection(token_id);
+            assert!(!tier.in_ranges(token_id), "Token {} is already in the ranges of the tier", token_id);
+            assert!(
+                self.tier_by_token_id.insert(&token_id, &name).is_none(),
+                "Token {} already belongs to a tier",
+                token_id
+            );
+            tier.token_count += 1;
+            //the ID no longer counts towards the tier whose range it falls in
+            if let Some((range_name, mut range_tier)) = self.internal_range_tier(token_id) {
+                range_tier.overridden += 1;
+                self.tiers.insert(&range_name, &range_tier);
+            }
+        }
+        self.tiers.insert(&name, &tier);
+    }
+
+    //remove token IDs added one by one from their tier
+    pub fn remove_tier_token_ids(&mut self, token_ids: Vec<u16>) {
+        self.assert_role(Role::Admin);
+        self.assert_tiers_not_locked();
+        for token_id in token_ids {
+            let name = self.tier_by_token_id.remove(&token_id).expect("Token doesn't belong to a tier");
+            let mut tier = self.tiers.get(&name).unwrap();
+            tier.token_count -= 1;
+            self.tiers.insert(&name, &tier);
+            //the ID counts towards the tier whose range it falls in again
+            if let Some((range_name, mut range_tier)) = self.internal_range_tier(token_id) {
+                range_tier.overridden -= 1;
+                self.tiers.insert(&range_name, &range_tier);
+            }
+        }
+    }
+
+    //get every tier along with how many of its tokens can still be minted
+    pub fn get_tiers(&self) -> Vec<JsonTier> {
+        //how many tokens of each tier were minted would give the class of the latest tokens away until the reveal
+        let hidden = self.internal_artwork_hidden();
+        self.tiers.iter()
+            .map(|(name, tier)| {
+                let tier = if hidden { Tier { minted: 0, ..tier } } else { tier };
+                JsonTier { name, remaining: tier.remaining(), tier }
+            })
+            .collect()
+    }
+
+    //get the name of the tier the given token ID belongs to, which is the tier of its artwork. It is hidden until the reveal
+    pub fn get_token_tier(&self, token_id: u16) -> Option<String> {
+        if self.internal_artwork_hidden() {
+            return None;
+        }
+        let artwork_id = self.internal_artwork_id(token_id)?;
+        self.internal_token_tier(artwork_id).map(|(name, _)| name)
+    }
+}
+
+impl Contract {
+    //make sure no token was taken out of the pool yet
+    pub(crate) fn assert_tiers_not_locked(&self) {
+        assert!(
+            !self.internal_minting_started(),
+            "Tiers cannot be changed once minting started"
+        );
+    }
+
+    //get the tier the given artwork ID belongs to. IDs added one by one take precedence over ranges
+    pub(crate) fn internal_token_tier(&self, token_id: u16) -> Option<(String, Tier)> {
+        if let Some(name) = self.tier_by_token_id.get(&token_id) {
+            return self.tiers.get(&name).map(|tier| (name, tier));
+        }
+        self.internal_range_tier(token_id)
+    }
+
+    //get the tier whose ranges the given token ID falls in, ignoring IDs added one by one
+    pub(crate) fn internal_range_tier(&self, token_id: u16) -> Option<(String, Tier)> {
+        self.tiers.iter().find(|(_, tier)| tier.in_ranges(token_id))
+    }
+
+    //get the number of token IDs still in the pool that can't be minted because their tier is sold out
+    pub(crate) fn internal_stranded_count(&self) -> u32 {
+        self.tiers.values().map(|tier| tier.stranded()).sum()
+    }
+
+    //take a token ID of a sold-out tier out of the pool without minting it
+    pub(crate) fn internal_discard_token_id(&mut self, slot: u32) {
+        let token_id = self.token_ids.swap_remove(slot);
+        let artwork_id = self.internal_artwork_id(token_id).unwrap();
+        let (name, mut tier) = self.internal_token_tier(artwork_id).unwrap();
+        tier.discarded += 1;
+        self.tiers.insert(&name, &tier);
+    }
+
+    //check if the given token ID can be minted without going over the supply of its artwork's tier
+    pub(crate) fn internal_tier_available(&self, token_id: u16) -> bool {
+        //tiers have no supply while the artwork of the tokens isn't known
+        let artwork_id = match self.internal_artwork_id(token_id) {
+            Some(artwork_id) => artwork_id,
+            None => return true,
+        };
+        !matches!(self.internal_token_tier(artwork_id), Some((_, tier)) if tier.is_sold_out())
+    }
+
+    //count a freshly minted token with the given artwork ID towards its tier and return the tier
+    pub(crate) fn internal_record_tier_mint(&mut self, artwork_id: u16) -> Option<(String, Tier)> {
+        let (name, mut tier) = self.internal_token_tier(artwork_id)?;
+        assert!(!tier.is_sold_out(), "The {} tier is sold out", name);
+        tier.minted += 1;
+        self.tiers.insert(&name, &tier);
+        Some((name, tier))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    #[test]
+    fn default_tiers() {
+        let contract = setup_contract(0);
+        assert_eq!(contract.get_token_tier(120), Some("gold".to_string()));
+        assert_eq!(contract.get_token_tier(121), Some("red".to_string()));
+        assert_eq!(contract.get_token_tier(666), Some("black".to_string()));
+        assert_eq!(contract.get_tiers().iter().map(|tier| tier.remaining).sum::<u32>(), 666);
+    }
+
+    #[test]
+    fn explicit_ids_take_precedence() {
+        let mut contract = setup_contract(0);
+        contract.set_tier("legendary".to_string(), Tier {
+            ranges: vec![],
+            price: None,
+            supply: None,
+            ..Default::default()
+        });
+        contract.add_tier_token_ids("legendary".to_string(), vec![7]);
+        assert_eq!(contract.get_token_tier(7), Some("legendary".to_string()));
+        //the ID is no longer counted in the gold tier
+        assert_eq!(contract.get_tiers().iter().map(|tier| tier.remaining).sum::<u32>(), 666);
+    }
+
+    #[test]
+    fn sold_out_tiers_are_left_out_of_the_counts() {
+        let mut contract = setup_collection(10, TokenIdPool::Range { from: 1, to: 10 });
+        contract.set_tier("rare".to_string(), Tier {
+            ranges: vec![(1, 5)],
+            price: None,
+            supply: Some(1),
+            ..Default::default()
+        });
+        contract.set_reserve_size(6);
+        assert_eq!(contract.internal_available_count(), 10);
+        contract.nft_airdrop_ids(vec![(accounts(1), vec![1])]);
+        assert_eq!(contract.internal_available_count(), 5);
+        assert!(contract.get_remaining_ids(None, None).iter().all(|token_id| *token_id > 5));
+
+        //every draw succeeds until the tokens that can still be minted run out
+        let token_ids = contract.nft_airdrop(vec![(accounts(1), 5)]);
+        assert!(token_ids.iter().all(|token_id| token_id.parse::<u16>().unwrap() > 5));
+        assert_eq!(contract.internal_available_count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Remove the token IDs added one by one before changing tier ranges")]
+    fn ranges_are_set_before_ids_are_added_one_by_one() {
+        let mut contract = setup_contract(0);
+        contract.set_tier("legendary".to_string(), Tier {
+            ranges: vec![],
+            price: None,
+            supply: None,
+            ..Default::default()
+        });
+        contract.add_tier_token_ids("legendary".to_string(), vec![7]);
+        contract.set_tier("gold".to_string(), Tier {
+            ranges: vec![(1, 100)],
+            price: None,
+            supply: None,
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Tier ranges overlap with the gold tier")]
+    fn tier_ranges_cannot_overlap() {
+        let mut contract = setup_contract(0);
+        contract.set_tier("silver".to_string(), Tier {
+            ranges: vec![(100, 130)],
+            price: None,
+            supply: None,
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Tier ranges overlap each other")]
+    fn ranges_of_a_tier_cannot_overlap() {
+        let mut contract = setup_contract(0);
+        contract.set_tier("gold".to_string(), Tier {
+            ranges: vec![(1, 60), (50, 120)],
+            price: None,
+            supply: None,
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn sold_out_tiers_are_not_drawn() {
+        let mut contract = setup_contract(0);
+        contract.set_tier("gold".to_string(), Tier {
+            ranges: vec![(1, 120)],
+            price: None,
+            supply: Some(0),
+            ..Default::default()
+        });
+        contract.set_reserve_size(50);
+        let token_ids = contract.nft_airdrop(vec![(accounts(1), 50)]);
+        assert!(token_ids.iter().all(|token_id| token_id.parse::<u16>().unwrap() > 120));
+    }
+
+    #[test]
+    fn type_index_follows_mints_and_burns() {
+        let mut contract = setup_with_tokens(vec![5]);
+        let token_id = "5".to_string();
+        assert_eq!(contract.nft_supply_for_type("gold".to_string()).0, 1);
+        assert_eq!(contract.nft_tokens_for_type("gold".to_string(), None, None)[0].token_id, token_id);
+
+        set_caller(accounts(1), 1);
+        contract.nft_burn(token_id, None);
+        assert_eq!(contract.nft_supply_for_type("gold".to_string()).0, 0);
+    }
+}
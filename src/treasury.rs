@@ -0,0 +1,226 @@
+use crate::*;
+
+//maximum number of accounts the mint proceeds can be split between
+const MAX_REVENUE_SPLIT_RECEIVERS: usize = 10;
+
+//how the mint proceeds are split between the collection's treasuries
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RevenueSplit {
+    //share of the proceeds for each account in basis points. The shares must add up to 10000
+    pub shares: HashMap<AccountId, u32>,
+    //whether the proceeds are paid out on every mint or only when distribute_revenue is called
+    pub auto_distribute: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    //withdraw some of the contract balance, leaving enough behind to cover storage and undistributed proceeds. Only the owner can withdraw
+    #[payable]
+    pub fn withdraw(&mut self, amount: U128, to: AccountId) -> Promise {
+        //assert that the user attached exactly 1 yoctoNEAR for security reasons
+        assert_one_yocto();
+        self.assert_owner();
+
+        let available = self.internal_withdrawable_balance();
+        assert!(
+            amount.0 <= available,
+            "Cannot withdraw more than {} yoctoNEAR",
+            available
+        );
+        Promise::new(to).transfer(amount.0)
+    }
+
+    //set how the mint proceeds are split. Only the owner can change it. Proceeds collected under the previous split are paid out first
+    pub fn set_revenue_split(&mut self, shares: HashMap<AccountId, u32>, auto_distribute: bool) {
+        self.assert_owner();
+        assert!(!shares.is_empty(), "Revenue split must have at least one receiver");
+        assert!(
+            shares.len() <= MAX_REVENUE_SPLIT_RECEIVERS,
+            "Cannot split revenue between more than {} receivers",
+            MAX_REVENUE_SPLIT_RECEIVERS
+        );
+        assert_eq!(
+            shares.values().map(|share| *share as u64).sum::<u64>(),
+            10_000,
+            "Revenue shares must add up to 10000 basis points"
+        );
+
+        self.internal_distribute_revenue();
+        self.revenue_split = Some(RevenueSplit { shares, auto_distribute });
+    }
+
+    //stop splitting the mint proceeds. Proceeds collected so far are paid out first
+    pub fn remove_revenue_split(&mut self) {
+        self.assert_owner();
+        self.internal_distribute_revenue();
+        self.revenue_split = None;
+    }
+
+    //pay out the proceeds collected since the last distribution according to the revenue split
+    pub fn distribute_revenue(&mut self) -> U128 {
+        U128(self.internal_distribute_revenue())
+    }
+
+    //get how the mint proceeds are split
+    pub fn get_revenue_split(&self) -> Option<RevenueSplit> {
+        self.revenue_split.clone()
+    }
+
+    //get the proceeds collected since the last distribution
+    pub fn get_pending_revenue(&self) -> U128 {
+        U128(self.pending_revenue)
+    }
+
+    //get how much of the contract balance can be withdrawn
+    pub fn get_withdrawable_balance(&self) -> U128 {
+        U128(self.internal_withdrawable_balance())
+    }
+}
+
+impl Contract {
+    //get the contract balance that isn't needed for storage staking or owed to the revenue split
+    pub(crate) fn internal_withdrawable_balance(&self) -> Balance {
+        let storage_cost = env::storage_byte_cost() * Balance::from(env::storage_usage());
+        env::account_balance()
+            .saturating_sub(storage_cost)
+            .saturating_sub(self.pending_revenue)
+    }
+
+    //account for the proceeds of a mint, paying them out straight away if the split is automatic
+    pub(crate) fn internal_collect_revenue(&mut self, amount: Balance) {
+        if let Some(revenue_split) = self.revenue_split.as_ref() {
+            self.pending_revenue += amount;
+            if revenue_split.auto_distribute {
+                self.internal_distribute_revenue();
+            }
+        }
+    }
+
+    //pay out the pending proceeds according to the revenue split and return the amount paid out
+    pub(crate) fn internal_distribute_revenue(&mut self) -> Balance {
+        let revenue_split = match self.revenue_split.as_ref() {
+            Some(revenue_split) => revenue_split,
+            None => return 0,
+        };
+
+        let mut distributed = 0;
+        for (account_id, share) in revenue_split.shares.iter() {
+            let payout = royalty_to_payout(*share, self.pending_revenue).0;
+            if payout > 0 {
+                Promise::new(account_id.clone()).transfer(payout);
+                distributed += payout;
+            }
+        }
+
+        //any rounding dust stays in the contract and becomes withdrawable
+        self.pending_revenue = 0;
+        distributed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{accounts, get_created_receipts};
+
+    //get the amount transferred to the account by the receipts created so far
+    fn transferred_to(account_id: &AccountId) -> Balance {
+        get_created_receipts()
+            .into_iter()
+            .filter(|receipt| receipt.receiver_id == *account_id)
+            .flat_map(|receipt| receipt.actions)
+            .filter_map(|action| match action {
+                VmAction::Transfer { deposit } => Some(deposit),
+                _ => None,
+            })
+            .sum()
+    }
+
+    #[test]
+    #[should_panic(expected = "Owner's method")]
+    fn admins_cannot_withdraw() {
+        let mut contract = setup_contract(0);
+        contract.grant_role(Role::Admin, accounts(1));
+        set_caller(accounts(1), 1);
+        contract.withdraw(U128(1), accounts(1));
+    }
+
+    #[test]
+    fn withdrawable_balance_keeps_storage_and_pending_revenue() {
+        let mut contract = setup_contract(0);
+        contract.set_revenue_split(HashMap::from([(accounts(1), 10_000)]), false);
+        contract.internal_collect_revenue(ONE_NEAR);
+
+        let storage_cost = env::storage_byte_cost() * Balance::from(env::storage_usage());
+        assert_eq!(contract.get_withdrawable_balance().0, env::account_balance() - storage_cost - ONE_NEAR);
+
+        //once paid out, the proceeds are no longer held back
+        contract.distribute_revenue();
+        assert_eq!(contract.get_withdrawable_balance().0, env::account_balance() - storage_cost);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot withdraw more than")]
+    fn pending_revenue_cannot_be_withdrawn() {
+        let mut contract = setup_contract(0);
+        contract.set_revenue_split(HashMap::from([(accounts(1), 10_000)]), false);
+        contract.internal_collect_revenue(ONE_NEAR);
+
+        let storage_cost = env::storage_byte_cost() * Balance::from(env::storage_usage());
+        set_caller(accounts(0), 1);
+        contract.withdraw(U128(env::account_balance() - storage_cost), accounts(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Revenue shares must add up to 10000 basis points")]
+    fn revenue_shares_must_add_up() {
+        let mut contract = setup_contract(0);
+        contract.set_revenue_split(HashMap::from([(accounts(1), 7_000), (accounts(2), 2_000)]), false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Revenue split must have at least one receiver")]
+    fn revenue_split_needs_a_receiver() {
+        let mut contract = setup_contract(0);
+        contract.set_revenue_split(HashMap::new(), false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot split revenue between more than 10 receivers")]
+    fn revenue_split_receivers_are_capped() {
+        let mut contract = setup_contract(0);
+        //one large share and ten small ones still add up to 10000
+        let mut shares: HashMap<AccountId, u32> = (1..=10)
+            .map(|i| (format!("receiver{}.near", i).parse().unwrap(), 100))
+            .collect();
+        shares.insert(accounts(1), 9_000);
+        contract.set_revenue_split(shares, false);
+    }
+
+    #[test]
+    fn revenue_is_distributed_by_share() {
+        let mut contract = setup_contract(0);
+        contract.set_revenue_split(HashMap::from([(accounts(1), 7_000), (accounts(2), 3_000)]), false);
+        contract.internal_collect_revenue(10 * ONE_NEAR);
+        assert_eq!(contract.get_pending_revenue().0, 10 * ONE_NEAR);
+
+        assert_eq!(contract.distribute_revenue().0, 10 * ONE_NEAR);
+        assert_eq!(transferred_to(&accounts(1)), 7 * ONE_NEAR);
+        assert_eq!(transferred_to(&accounts(2)), 3 * ONE_NEAR);
+        assert_eq!(contract.get_pending_revenue().0, 0);
+    }
+
+    #[test]
+    fn automatic_split_pays_out_on_collection() {
+        let mut contract = setup_contract(0);
+        contract.set_revenue_split(HashMap::from([(accounts(1), 5_000), (accounts(2), 5_000)]), true);
+        contract.internal_collect_revenue(ONE_NEAR);
+
+        assert_eq!(transferred_to(&accounts(1)), ONE_NEAR / 2);
+        assert_eq!(transferred_to(&accounts(2)), ONE_NEAR / 2);
+        assert_eq!(contract.get_pending_revenue().0, 0);
+    }
+}
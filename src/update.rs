@@ -0,0 +1,188 @@
+use crate::*;
+
+//the fields of a token's metadata to change. Fields left out keep their value
+#[derive(Serialize, Deserialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenMetadataPatch {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub media: Option<String>,
+    pub media_hash: Option<Base64VecU8>,
+    pub expires_at: Option<u64>,
+    pub starts_at: Option<u64>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<Base64VecU8>,
+    //replaces every attribute of the token, except its class
+    pub attributes: Option<Vec<TokenAttribute>>,
+}
+
+#[near_bindgen]
+impl Contract {
+    //correct the metadata of a minted token. Tokens whose metadata is frozen can't be updated
+    pub fn nft_update_metadata(&mut self, token_id: TokenId, patch: TokenMetadataPatch) {
+        self.assert_role(Role::Admin);
+        self.assert_metadata_not_frozen(&token_id);
+
+        if let Some(attributes) = patch.attributes {
+            //the attributes belong to the artwork the token shows
+            let artwork_id = self.internal_artwork_key(&token_id).expect("The artwork of the token isn't known yet");
+            self.internal_set_token_attributes(&artwork_id, attributes);
+        }
+
+        let mut metadata = self.token_metadata_by_id.get(&token_id).expect("No token");
+        let set = |field: &mut Option<String>, value: Option<String>| {
+            if value.is_some() {
+                *field = value;
+            }
+        };
+        set(&mut metadata.title, patch.title);
+        set(&mut metadata.description, patch.description);
+        set(&mut metadata.media, patch.media);
+        set(&mut metadata.reference, patch.reference);
+        metadata.media_hash = patch.media_hash.or(metadata.media_hash);
+        metadata.reference_hash = patch.reference_hash.or(metadata.reference_hash);
+        metadata.expires_at = patch.expires_at.or(metadata.expires_at);
+        metadata.starts_at = patch.starts_at.or(metadata.starts_at);
+        metadata.updated_at = Some(env::block_timestamp() / 1_000_000);
+        metadata.assert_valid();
+        self.token_metadata_by_id.insert(&token_id, &metadata);
+
+        log_nft_metadata_update(vec![token_id], None);
+    }
+
+    /*
+        permanently freeze the metadata of the given tokens, or of the whole collection if no token is given.
+        The metadata of a frozen token no longer follows the template nor the base URI of the contract metadata, its media and
+        reference are kept as absolute URLs. Once the whole collection is frozen the base URI can no longer be changed.
+        This can only be done once the artwork is revealed
+    */
+    pub fn freeze_metadata(&mut self, token_ids: Option<Vec<TokenId>>) {
+        self.assert_role(Role::Admin);
+        assert!(
+            !self.reveal.is_hidden() && !self.internal_artwork_pending(),
+            "Metadata cannot be frozen before the reveal"
+        );
+
+        match token_ids {
+            Some(token_ids) => {
+                let base_uri = self.metadata.get().unwrap().base_uri;
+                for token_id in token_ids {
+                    //keep the metadata the token shows right now, whatever happens to the template
+                    let token = self.tokens_by_id.get(&token_id).expect("No token");
+                    let metadata = self.token_metadata_by_id.get(&token_id).unwrap();
+                    let mut metadata = self.internal_resolve_metadata(&token_id, token.token_type.as_ref(), metadata);
+                    metadata.media = metadata.media.map(|media| absolute_url(base_uri.as_deref(), media));
+                    metadata.reference = metadata.reference.map(|reference| absolute_url(base_uri.as_deref(), reference));
+                    metadata.assert_valid();
+                    self.token_metadata_by_id.insert(&token_id, &metadata);
+                    self.frozen_token_ids.insert(&token_id);
+                }
+            }
+            None => self.metadata_frozen = true,
+        }
+    }
+
+    //check if the metadata of the given token, or of the whole collection if no token is given, is frozen
+    pub fn is_metadata_frozen(&self, token_id: Option<TokenId>) -> bool {
+        self.metadata_frozen || token_id.is_some_and(|token_id| self.frozen_token_ids.contains(&token_id))
+    }
+}
+
+impl Contract {
+    //make sure the metadata of the given token can still be changed
+    pub(crate) fn assert_metadata_not_frozen(&self, token_id: &TokenId) {
+        assert!(!self.metadata_frozen, "Metadata is frozen");
+        assert!(
+            !self.frozen_token_ids.contains(token_id),
+            "Metadata of token {} is frozen",
+            token_id
+        );
+    }
+
+    //make sure the metadata of the token showing the given artwork can still be changed
+    pub(crate) fn assert_artwork_not_frozen(&self, artwork_id: &TokenId) {
+        match self.internal_artwork_token_key(artwork_id) {
+            Some(token_id) => self.assert_metadata_not_frozen(&token_id),
+            //no token can be frozen before its artwork is known
+            None => assert!(!self.metadata_frozen, "Metadata is frozen"),
+        }
+    }
+}
+
+//prefix the given URL with the base URI if it is relative to it
+fn absolute_url(base_uri: Option<&str>, url: String) -> String {
+    match base_uri {
+        Some(base_uri) if !url.contains("://") && !url.starts_with("data:") => {
+            format!("{}/{}", base_uri.trim_end_matches('/'), url.trim_start_matches('/'))
+        }
+        _ => url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::get_logs;
+
+    #[test]
+    fn metadata_is_patched() {
+        let mut contract = setup_with_tokens(vec![5]);
+        contract.nft_update_metadata("5".to_string(), TokenMetadataPatch {
+            media: Some("ipfs://fixed.gif".to_string()),
+            media_hash: Some(Base64VecU8(vec![1; 32])),
+            attributes: Some(vec![attribute("Hat", "Crown")]),
+            ..Default::default()
+        });
+
+        let metadata = contract.nft_token("5".to_string()).unwrap().metadata;
+        assert_eq!(metadata.media.unwrap(), "ipfs://fixed.gif");
+        assert!(metadata.extra.unwrap().contains("Crown"));
+        assert!(metadata.updated_at.is_some());
+        assert!(get_logs().last().unwrap().contains(r#""event":"nft_metadata_update","data":[{"token_ids":["5"]}]"#));
+    }
+
+    #[test]
+    fn frozen_tokens_keep_their_metadata() {
+        let mut contract = setup_with_tokens(vec![5]);
+        contract.set_content_hashes(HashMap::from([("5".to_string(), ContentHashes {
+            media_hash: Base64VecU8(vec![1; 32]),
+            reference_hash: Some(Base64VecU8(vec![2; 32])),
+        })]));
+        contract.freeze_metadata(Some(vec!["5".to_string()]));
+        contract.set_metadata_template(MetadataTemplate {
+            title: Some("Giggle {id}".to_string()),
+            ..contract.get_metadata_template()
+        });
+        contract.set_contract_metadata(NFTContractMetadata {
+            base_uri: Some("https://example.com".to_string()),
+            ..contract.nft_metadata()
+        });
+
+        let metadata = contract.nft_token("5".to_string()).unwrap().metadata;
+        assert_eq!(metadata.title.unwrap(), "Mr Giggles #5");
+        assert_eq!(metadata.media.unwrap(), "https://ipfs.io/ipfs/QmXXsDoynSQpinPRSqu6RGJwSHkkSFA1k2xX7r8kco557P/5.gif");
+        assert!(contract.is_metadata_frozen(Some("5".to_string())));
+    }
+
+    #[test]
+    #[should_panic(expected = "Media hash is required when media is set")]
+    fn patched_media_needs_a_hash() {
+        let mut contract = setup_with_tokens(vec![5]);
+        contract.nft_update_metadata("5".to_string(), TokenMetadataPatch {
+            media: Some("ipfs://fixed.gif".to_string()),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Metadata is frozen")]
+    fn frozen_collection_rejects_updates() {
+        let mut contract = setup_with_tokens(vec![5]);
+        contract.freeze_metadata(None);
+        contract.nft_update_metadata("5".to_string(), TokenMetadataPatch {
+            title: Some("Changed".to_string()),
+            ..Default::default()
+        });
+    }
+}
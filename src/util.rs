@@ -1,108 +0,0 @@
-pub const RAND_TOKEN_IDS: [u16; 2000] = [
-569, 1241, 1421, 915, 4, 1266, 1561, 1318, 1786, 1922, 2000, 818, 42, 1392, 1842, 216, 1412, 1370, 747, 1021, 513, 
-1343, 33, 1366, 617, 1661, 101, 91, 557, 1905, 1252, 402, 792, 1118, 1081, 1737, 392, 438, 434, 1099, 1450, 1872, 1315, 
-1016, 1509, 1322, 1325, 707, 876, 1345, 1721, 1623, 1667, 763, 1207, 961, 286, 743, 964, 1904, 93, 983, 533, 1613, 1090, 
-499, 372, 1800, 672, 115, 1407, 830, 1957, 1733, 82, 404, 1398, 616, 279, 1736, 1608, 968, 1336, 1588, 1256, 1267, 1859, 
-1015, 341, 436, 1279, 1088, 1170, 166, 1971, 1707, 1978, 26, 1575, 1889, 16, 1717, 1436, 397, 262, 1040, 1780, 519, 
-1214, 1179, 1101, 1818, 579, 1789, 1898, 1160, 686, 522, 1651, 801, 1809, 1257, 418, 1525, 1506, 143, 1742, 755, 207, 
-843, 816, 1237, 1642, 1079, 1293, 535, 1286, 1253, 1674, 528, 1505, 684, 227, 544, 608, 1280, 1005, 61, 1274, 1544, 556, 
-1763, 1806, 484, 711, 475, 1493, 1238, 928, 1240, 1522, 1572, 593, 1791, 1745, 65, 473, 1310, 1475, 1111, 618, 1635, 
-1927, 943, 1176, 1604, 664, 11, 1459, 629, 86, 838, 1466, 675, 1298, 1379, 1867, 1496, 1195, 1868, 1906, 782, 1377, 426, 
-479, 630, 416, 1037, 1783, 396, 399, 636, 206, 871, 251, 1389, 972, 156, 1367, 373, 1332, 270, 1675, 1841, 31, 1162, 
-133, 1003, 1955, 702, 1936, 1719, 906, 1224, 410, 666, 107, 1659, 1041, 713, 720, 108, 1229, 99, 225, 1847, 927, 1557, 
-1774, 451, 274, 858, 1474, 1963, 1461, 1534, 1624, 282, 427, 1202, 1138, 305, 1126, 1860, 1917, 1152, 1770, 69, 1495, 
-587, 604, 102, 1026, 642, 683, 1057, 1247, 1047, 1761, 633, 935, 605, 344, 1480, 184, 594, 612, 1180, 130, 1952, 1929, 
-510, 1792, 1494, 1125, 773, 1245, 1752, 123, 1108, 260, 1617, 1967, 1937, 1722, 1570, 1909, 1134, 701, 1275, 1995, 447, 
-967, 161, 391, 1012, 909, 841, 1843, 1862, 543, 1990, 1242, 331, 800, 1104, 530, 30, 680, 1650, 32, 419, 1524, 917, 27, 
-1852, 1151, 432, 72, 1365, 1696, 1137, 1039, 1409, 1173, 1626, 1121, 1112, 778, 649, 1291, 1023, 1812, 596, 1087, 844, 
-1, 1630, 1969, 1236, 1593, 659, 367, 529, 805, 913, 542, 959, 553, 132, 320, 1424, 306, 98, 340, 1882, 79, 1804, 1886, 
-1321, 1248, 1652, 846, 1945, 1853, 548, 44, 674, 1165, 872, 125, 765, 1451, 839, 1731, 1335, 1979, 1171, 1050, 681, 
-592, 1832, 1075, 300, 1698, 656, 627, 744, 1725, 994, 1919, 151, 1368, 1210, 226, 1059, 523, 952, 1802, 599, 110, 657, 
-934, 322, 1058, 813, 1056, 1454, 991, 39, 355, 1350, 1187, 1941, 454, 825, 831, 1344, 203, 379, 1878, 1228, 1352, 273, 
-239, 1907, 1489, 487, 1596, 506, 1155, 1007, 1858, 1456, 1028, 1808, 471, 1177, 880, 789, 1960, 573, 536, 1400, 476, 
-1730, 387, 1769, 884, 1532, 309, 1357, 95, 1581, 992, 167, 1239, 1150, 571, 152, 1668, 1404, 1130, 1890, 1845, 1201, 
-264, 774, 1603, 515, 1983, 1455, 597, 501, 308, 689, 957, 824, 1518, 1364, 1411, 459, 323, 1810, 1796, 104, 1074, 1362, 
-734, 1272, 1363, 939, 73, 307, 894, 653, 269, 1360, 892, 853, 261, 1206, 1249, 1183, 24, 1816, 1598, 1399, 1481, 1255, 
-1002, 78, 1870, 1536, 1418, 1606, 111, 1086, 758, 491, 1222, 1066, 303, 1307, 595, 956, 645, 1200, 1094, 1647, 1153, 
-142, 806, 1209, 1879, 371, 1428, 1420, 1109, 508, 987, 1375, 911, 1319, 1346, 1349, 1085, 1268, 1504, 297, 1966, 1930, 
-405, 1413, 1278, 190, 1053, 1655, 978, 1072, 1218, 393, 990, 113, 406, 1408, 295, 1427, 1531, 1514, 729, 100, 1693, 
-769, 1038, 231, 187, 183, 1887, 1326, 83, 302, 762, 232, 1013, 1944, 643, 866, 353, 217, 1483, 632, 1143, 1133, 1423, 
-1031, 258, 88, 1897, 1689, 1974, 64, 1464, 312, 857, 1711, 328, 1106, 899, 655, 1819, 771, 398, 321, 783, 1949, 1142, 
-709, 1018, 293, 214, 1226, 1164, 1643, 1914, 22, 1741, 1285, 574, 1190, 1410, 49, 1528, 234, 503, 1986, 314, 1837, 482, 
-1084, 1546, 219, 467, 568, 976, 290, 1283, 873, 1149, 139, 1701, 1338, 746, 1829, 502, 1135, 76, 932, 1277, 1096, 241, 
-521, 1984, 1501, 698, 584, 673, 357, 1458, 313, 1082, 1805, 1029, 1542, 1746, 1355, 590, 1813, 1584, 1574, 1110, 1154, 
-465, 51, 588, 128, 1472, 919, 1419, 1009, 785, 425, 68, 495, 1866, 347, 1699, 1975, 1431, 1264, 1605, 1577, 1391, 209, 
-797, 45, 1684, 766, 1743, 1679, 1324, 284, 654, 834, 1855, 938, 280, 1462, 793, 829, 1498, 712, 511, 893, 200, 1749, 
-1953, 55, 1527, 837, 537, 413, 1131, 1634, 254, 963, 1306, 1182, 1144, 738, 1386, 1981, 356, 370, 567, 202, 15, 325, 
-756, 1232, 174, 332, 94, 337, 1705, 256, 440, 220, 993, 360, 531, 1768, 821, 221, 614, 1993, 1482, 1976, 980, 1095, 
-147, 1246, 41, 639, 1815, 1760, 1446, 1980, 944, 912, 1683, 1764, 181, 1692, 1645, 1549, 1540, 1158, 1747, 120, 682, 
-1848, 507, 575, 310, 1884, 856, 46, 452, 710, 1128, 1523, 807, 1449, 1469, 1507, 212, 693, 1402, 1091, 255, 1127, 414, 
-119, 330, 1940, 1923, 1627, 1488, 647, 1235, 981, 1353, 335, 965, 1383, 1548, 48, 696, 606, 1287, 25, 395, 1553, 1947, 
-1393, 1064, 185, 842, 444, 1757, 554, 47, 50, 394, 540, 998, 1193, 388, 1033, 819, 420, 1467, 1019, 796, 1946, 971, 
-277, 731, 114, 947, 1024, 1100, 848, 708, 1989, 287, 1098, 580, 1217, 598, 814, 646, 541, 381, 74, 1560, 492, 607, 
-1569, 1900, 1203, 358, 1417, 1695, 1331, 1503, 1740, 240, 36, 714, 237, 87, 526, 1893, 1723, 601, 890, 263, 466, 1625, 
-940, 1116, 1704, 1629, 1709, 1580, 333, 1615, 1422, 1765, 742, 96, 89, 705, 1374, 1585, 882, 804, 650, 1223, 1895, 21, 
-1323, 480, 1666, 1756, 1395, 60, 877, 1915, 1259, 1835, 1885, 160, 988, 1934, 1329, 1962, 791, 1672, 570, 1330, 619, 
-948, 109, 1192, 1309, 1788, 267, 752, 283, 485, 272, 1718, 827, 1212, 609, 867, 1508, 1093, 1564, 671, 1797, 985, 336, 
-907, 572, 1824, 383, 165, 1516, 679, 1708, 469, 854, 136, 246, 23, 1925, 904, 1221, 896, 415, 767, 1849, 1468, 1782, 
-1861, 1452, 561, 1102, 637, 658, 63, 250, 180, 1902, 1356, 134, 1644, 1051, 252, 493, 874, 1602, 122, 455, 253, 
-733, 945, 623, 1017, 979, 20, 1660, 334, 470, 474, 1595, 1308, 375, 363, 1406, 576, 458, 1712, 922, 1113, 57, 799, 
-1640, 1738, 199, 622, 1589, 626, 1369, 401, 1694, 210, 1781, 1384, 10, 931, 652, 591, 1020, 1371, 1205, 942, 621, 339, 
-1052, 1899, 1844, 1317, 460, 1563, 448, 1004, 80, 1714, 802, 222, 694, 1271, 560, 28, 412, 1912, 1856, 901, 144, 692, 
-1775, 105, 1713, 740, 1216, 1558, 70, 1662, 641, 1188, 268, 1185, 1394, 326, 1225, 58, 1304, 173, 1114, 137, 1726, 826, 
-727, 247, 1478, 1513, 725, 665, 760, 925, 84, 1068, 1479, 439, 1799, 461, 1597, 457, 380, 1430, 149, 1000, 1767, 850, 
-1901, 929, 817, 851, 1646, 218, 1619, 1618, 1415, 400, 1333, 969, 1258, 1435, 201, 581, 670, 953, 1136, 798, 1036, 
-1157, 1970, 1517, 898, 1299, 291, 463, 1700, 168, 316, 429, 71, 369, 951, 1566, 1682, 1161, 823, 1811, 648, 259, 1396, 
-1372, 304, 1262, 421, 1339, 852, 1639, 836, 208, 1755, 1579, 1871, 1211, 182, 1443, 1294, 2, 1231, 1080, 1739, 1043, 
-1208, 1965, 691, 1687, 1612, 1105, 1055, 1334, 1320, 35, 298, 1778, 669, 878, 868, 271, 1502, 973, 1425, 748, 1954, 
-794, 1453, 1537, 780, 1628, 40, 59, 1609, 77, 1956, 845, 1985, 1826, 1388, 1814, 1463, 751, 1772, 296, 1378, 1834, 
-750, 600, 999, 1998, 1827, 1146, 1724, 1103, 1347, 1864, 1172, 1373, 1073, 1213, 135, 1576, 602, 301, 534, 828, 1006, 
-1181, 1526, 914, 118, 958, 1676, 141, 545, 1244, 281, 1273, 582, 1243, 430, 887, 861, 1803, 1828, 583, 1065, 1658, 
-194, 1690, 1653, 1732, 433, 244, 1067, 527, 423, 327, 1562, 1671, 1649, 615, 294, 1968, 514, 1964, 1069, 717, 1881, 
-1754, 1303, 704, 1174, 1048, 564, 620, 1833, 703, 1586, 881, 862, 1831, 17, 1070, 1441, 719, 1759, 538, 1030, 1751, 
-732, 481, 1477, 754, 1850, 1044, 319, 127, 1204, 129, 497, 888, 472, 1538, 1578, 1305, 1521, 1823, 1550, 1677, 1920, 
-75, 278, 803, 1269, 1582, 154, 1263, 1987, 1918, 764, 668, 759, 288, 589, 1529, 329, 1773, 1060, 38, 362, 910, 1282, 
-718, 378, 1484, 97, 512, 1390, 1485, 348, 1215, 1444, 610, 450, 205, 1168, 676, 233, 238, 685, 1519, 248, 54, 1117, 
-1011, 1727, 1556, 549, 551, 1903, 883, 153, 85, 984, 1385, 92, 150, 895, 1197, 1123, 376, 1728, 1750, 870, 1027, 1132, 
-624, 1801, 1254, 1491, 695, 53, 384, 532, 1097, 505, 1681, 1414, 1857, 660, 1583, 1460, 634, 1328, 1996, 159, 1632, 
-661, 365, 1691, 869, 1594, 1189, 177, 1656, 431, 249, 1620, 178, 243, 787, 1380, 299, 1194, 1313, 1913, 745, 1991, 
-1178, 1077, 1863, 749, 428, 975, 112, 962, 1045, 728, 121, 1163, 352, 442, 1341, 1124, 1854, 810, 1716, 1568, 1530, 
-518, 1951, 1300, 62, 1533, 1545, 213, 52, 663, 768, 1251, 1552, 1497, 1686, 586, 1376, 667, 1875, 103, 1942, 6, 106, 
-753, 1492, 351, 930, 955, 490, 66, 790, 5, 1490, 1220, 409, 1434, 1465, 539, 275, 832, 1014, 1678, 435, 197, 1076, 
-1276, 1429, 169, 1416, 1779, 757, 1063, 145, 550, 1358, 770, 245, 1316, 1457, 1734, 215, 1817, 812, 1762, 188, 368, 
-1869, 1559, 486, 687, 1191, 625, 1148, 7, 289, 726, 1715, 840, 644, 1437, 446, 56, 1932, 464, 1610, 1260, 1145, 885, 
-366, 1924, 1622, 739, 517, 996, 1825, 126, 1159, 12, 923, 1565, 936, 875, 1141, 847, 891, 524, 924, 730, 822, 1688, 
-1641, 1442, 986, 1140, 849, 359, 1958, 235, 468, 1520, 1636, 795, 1139, 772, 1512, 229, 905, 946, 1295, 148, 1877, 
-1874, 1401, 1078, 498, 462, 198, 1515, 631, 403, 950, 1616, 1541, 1397, 1880, 1567, 1487, 566, 1703, 546, 230, 1910, 
-1230, 1735, 1083, 1156, 171, 1611, 445, 1288, 897, 578, 1994, 1911, 1590, 1706, 350, 628, 449, 1790, 504, 90, 1289, 
-176, 879, 1931, 117, 1511, 516, 1883, 1175, 164, 1062, 349, 483, 37, 496, 1664, 1876, 1999, 1438, 552, 1499, 982, 
-1830, 1573, 343, 1448, 204, 520, 1092, 477, 585, 776, 1284, 1198, 1433, 1821, 236, 1670, 1554, 1865, 1337, 1042, 
-1071, 196, 1035, 1234, 170, 859, 1839, 1776, 577, 558, 1440, 1587, 1933, 960, 146, 1961, 266, 525, 1753, 1129, 1943, 
-386, 116, 276, 1089, 1591, 192, 966, 191, 3, 1988, 189, 662, 1784, 1939, 179, 1049, 1120, 706, 345, 1290, 1766, 937, 
-1340, 1928, 690, 1680, 1748, 1169, 1500, 478, 1342, 1982, 1387, 1926, 157, 863, 835, 1196, 1382, 1992, 1892, 408, 565, 
-1166, 437, 781, 1348, 364, 699, 722, 9, 1167, 1896, 1250, 172, 1302, 1758, 611, 1147, 563, 933, 1648, 860, 737, 342, 
-651, 1851, 1607, 1184, 1657, 555, 1311, 902, 808, 1486, 29, 1665, 761, 900, 855, 81, 494, 997, 677, 1301, 1787, 786, 
-509, 1025, 1592, 920, 547, 1697, 1270, 317, 1891, 1122, 417, 1199, 1888, 1798, 1744, 124, 1314, 1445, 1471, 1795, 949, 
-1720, 1908, 820, 716, 211, 1426, 1219, 1022, 155, 724, 158, 1555, 1403, 603, 1631, 811, 1054, 865, 8, 1439, 162, 1935, 
-1281, 1186, 1685, 638, 374, 1633, 921, 1838, 1473, 1973, 1840, 1729, 1547, 1621, 285, 1470, 315, 735, 916, 1447, 1381, 
-1950, 1836, 1638, 1119, 1046, 1327, 138, 688, 389, 889, 1261, 775, 1292, 1673, 1010, 175, 456, 1793, 257, 377, 635, 
-989, 223, 1846, 1571, 941, 788, 193, 224, 338, 1405, 1600, 784, 390, 407, 886, 833, 1233, 1312, 1032, 1948, 1359, 
-34, 1001, 1551, 903, 441, 1822, 1008, 559, 1785, 736, 1807, 640, 1614, 908, 1663, 453, 43, 723, 1543, 131, 715, 324, 
-228, 1972, 1354, 346, 1351, 1296, 1997, 777, 1107, 424, 292, 1820, 1873, 562, 354, 1777, 864, 1361, 1654, 411, 779, 
-678, 265, 1710, 1115, 1921, 163, 1265, 815, 422, 954, 1702, 1894, 186, 700, 1916, 242, 13, 489, 1599, 1637, 741, 
-1432, 1794, 18, 995, 697, 613, 311, 385, 443, 926, 19, 195, 977, 1539, 970, 1535, 488, 1938, 361, 1771, 14, 382, 140, 
-809, 1959, 1601, 318, 974, 500, 918, 1977, 1476, 1227, 721, 1034, 1061, 67, 1510, 1669, 1297];
-
-pub fn get_type_by_id(token_id: u16) -> String {
-
-    let token_type = if token_id <=120 {
-        String::from("gold")
-    } else if token_id <= 660 {
-        String::from("red")
-    } else if token_id <= 1200 {
-        String::from("black")
-    } else {
-        String::from("white")
-    };
-    return token_type;
-}
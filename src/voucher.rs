@@ -0,0 +1,239 @@
+use crate::*;
+use ed25519_dalek::{PublicKey as Ed25519PublicKey, Signature};
+use near_sdk::CurveType;
+
+/*
+    a voucher signed off-chain by the voucher signer that lets an account mint without being on a list.
+    The signed message is the borsh serialization of the contract account ID followed by the voucher,
+    so a voucher issued for this contract can't be redeemed on another one.
+*/
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintVoucher {
+    pub account_id: AccountId, // the only account that can redeem the voucher. The tokens are minted to it
+    pub tier: SaleList,        // the list the mints are counted towards, ex. "og" for a presale giveaway
+    pub price: U128,           // price per token in yoctoNEAR
+    pub max_quantity: u32,     // maximum number of tokens that can be minted with the voucher
+    pub expires_at: u64,       // When the voucher stops being valid, Unix epoch in milliseconds
+    pub nonce: u64,            // unique number of the voucher. Its max quantity can be redeemed over several calls
+}
+
+#[near_bindgen]
+impl Contract {
+    //set the ed25519 public key that signs the mint vouchers. Passing None stops vouchers from being redeemed
+    pub fn set_voucher_signer(&mut self, public_key: Option<PublicKey>) {
+        self.assert_role(Role::Admin);
+        if let Some(public_key) = public_key.as_ref() {
+            assert!(
+                public_key.curve_type() == CurveType::ED25519,
+                "Voucher signer must be an ed25519 key"
+            );
+        }
+        self.voucher_signer = public_key;
+    }
+
+    //get the public key that signs the mint vouchers
+    pub fn get_voucher_signer(&self) -> Option<PublicKey> {
+        self.voucher_signer.clone()
+    }
+
+    //get how many tokens were already minted with the voucher of the given nonce
+    pub fn get_voucher_redeemed(&self, nonce: u64) -> u32 {
+        self.voucher_redeemed.get(&nonce).unwrap_or(0)
+    }
+
+    //mint random tokens using a voucher signed by the voucher signer. Defaults to what is left of the voucher's max quantity
+    #[payable]
+    pub fn nft_mint_with_voucher(
+        &mut self,
+        voucher: MintVoucher,
+        signature: Base64VecU8,
+        quantity: Option<u32>,
+    ) -> MintResult {
+        self.assert_minting_not_paused();
+        let redeemed = self.get_voucher_redeemed(voucher.nonce);
+        assert!(redeemed < voucher.max_quantity, "Voucher already used");
+        let quantity = quantity.unwrap_or(voucher.max_quantity - redeemed);
+        assert!(quantity > 0, "Quantity must be greater than 0");
+        assert!(
+            self.internal_sale_available_count() >= quantity,
+            "Exceeds max nfts"
+        );
+
+        //make sure the voucher was signed by the voucher signer and can still be redeemed by the caller
+        self.assert_valid_voucher_signature(&voucher, &signature);
+        assert_eq!(
+            env::predecessor_account_id(),
+            voucher.account_id,
+            "Voucher was issued to another account"
+        );
+        let curr_time = env::block_timestamp() / 1_000_000;
+        assert!(curr_time < voucher.expires_at, "Voucher expired");
+        assert!(
+            quantity <= voucher.max_quantity - redeemed,
+            "Voucher allows at most {} more mints",
+            voucher.max_quantity - redeemed
+        );
+
+        //measure the initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        //count the tokens towards the voucher so it can't be replayed past its max quantity
+        self.voucher_redeemed.insert(&voucher.nonce, &(redeemed + quantity));
+
+        let price = voucher.price.0 * quantity as u128;
+        assert!(
+            env::attached_deposit() >= price,
+            "Insufficient fund"
+        );
+
+        //draw and mint each of the tokens and count them towards the voucher's tier. The voucher price applies whatever their tiers
+        let (token_ids, _) = self.internal_mint_batch(&voucher.account_id, quantity, None, 0);
+        log_nft_mint(vec![NftMintLog {
+            owner_id: voucher.account_id.to_string(),
+            token_ids: token_ids.clone(),
+            memo: None,
+        }]);
+        self.internal_record_sale_mint(&voucher.account_id, voucher.tier == SaleList::Public, quantity);
+
+        self.internal_charge_mint(token_ids, initial_storage_usage, price)
+    }
+}
+
+impl Contract {
+    //make sure the signature is a valid signature of the voucher by the voucher signer
+    pub(crate) fn assert_valid_voucher_signature(&self, voucher: &MintVoucher, signature: &Base64VecU8) {
+        let signer = self.voucher_signer.as_ref().expect("Vouchers are not enabled");
+        //the first byte of a NEAR public key is its curve type
+        let public_key = Ed25519PublicKey::from_bytes(&signer.as_bytes()[1..])
+            .unwrap_or_else(|_| env::panic_str("Invalid voucher signer"));
+        let signature = Signature::from_bytes(&signature.0)
+            .unwrap_or_else(|_| env::panic_str("Invalid voucher signature"));
+
+        let message = (env::current_account_id(), voucher).try_to_vec().unwrap();
+        assert!(
+            public_key.verify_strict(&message, &signature).is_ok(),
+            "Invalid voucher signature"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ed25519_dalek::{Keypair, SecretKey, Signer};
+    use near_sdk::test_utils::accounts;
+
+    const PRICE: Balance = 1_000_000_000_000_000_000_000_000;
+
+    fn keypair() -> Keypair {
+        let secret = SecretKey::from_bytes(&[7; 32]).unwrap();
+        let public = (&secret).into();
+        Keypair { secret, public }
+    }
+
+    fn signer_public_key(keypair: &Keypair) -> PublicKey {
+        [vec![CurveType::ED25519 as u8], keypair.public.to_bytes().to_vec()]
+            .concat()
+            .try_into()
+            .unwrap()
+    }
+
+    fn voucher(nonce: u64) -> MintVoucher {
+        MintVoucher {
+            account_id: accounts(1),
+            tier: SaleList::Whitelist,
+            price: U128(PRICE),
+            max_quantity: 2,
+            expires_at: 2_000,
+            nonce,
+        }
+    }
+
+    fn sign(keypair: &Keypair, voucher: &MintVoucher) -> Base64VecU8 {
+        let message = (env::current_account_id(), voucher).try_to_vec().unwrap();
+        Base64VecU8(keypair.sign(&message).to_bytes().to_vec())
+    }
+
+    fn setup() -> Contract {
+        let mut contract = setup_contract(0);
+        contract.set_voucher_signer(Some(signer_public_key(&keypair())));
+        contract
+    }
+
+    #[test]
+    fn mint_with_voucher() {
+        let mut contract = setup();
+        let voucher = voucher(1);
+        let signature = sign(&keypair(), &voucher);
+
+        set_caller_at(accounts(1), 3 * PRICE, 1_000);
+        let result = contract.nft_mint_with_voucher(voucher, signature, None);
+        assert_eq!(result.token_ids.len(), 2);
+        assert_eq!(result.price.0, 2 * PRICE);
+        assert_eq!(contract.get_voucher_redeemed(1), 2);
+        assert_eq!(contract.get_presale_amount(accounts(1)), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Voucher already used")]
+    fn voucher_cannot_be_replayed() {
+        let mut contract = setup();
+        let voucher = voucher(1);
+        let signature = sign(&keypair(), &voucher);
+
+        set_caller_at(accounts(1), 3 * PRICE, 1_000);
+        contract.nft_mint_with_voucher(voucher.clone(), signature.clone(), None);
+        contract.nft_mint_with_voucher(voucher, signature, Some(1));
+    }
+
+    #[test]
+    fn voucher_can_be_redeemed_in_parts() {
+        let mut contract = setup();
+        let voucher = voucher(1);
+        let signature = sign(&keypair(), &voucher);
+
+        set_caller_at(accounts(1), 3 * PRICE, 1_000);
+        contract.nft_mint_with_voucher(voucher.clone(), signature.clone(), Some(1));
+        assert_eq!(contract.get_voucher_redeemed(1), 1);
+        let result = contract.nft_mint_with_voucher(voucher, signature, None);
+        assert_eq!(result.token_ids.len(), 1);
+        assert_eq!(contract.get_voucher_redeemed(1), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Voucher allows at most 1 more mints")]
+    fn voucher_parts_cannot_exceed_its_max_quantity() {
+        let mut contract = setup();
+        let voucher = voucher(1);
+        let signature = sign(&keypair(), &voucher);
+
+        set_caller_at(accounts(1), 3 * PRICE, 1_000);
+        contract.nft_mint_with_voucher(voucher.clone(), signature.clone(), Some(1));
+        contract.nft_mint_with_voucher(voucher, signature, Some(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid voucher signature")]
+    fn tampered_voucher_is_rejected() {
+        let mut contract = setup();
+        let mut voucher = voucher(1);
+        let signature = sign(&keypair(), &voucher);
+        voucher.price = U128(0);
+
+        set_caller_at(accounts(1), 3 * PRICE, 1_000);
+        contract.nft_mint_with_voucher(voucher, signature, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Voucher was issued to another account")]
+    fn voucher_is_bound_to_its_account() {
+        let mut contract = setup();
+        let voucher = voucher(1);
+        let signature = sign(&keypair(), &voucher);
+
+        set_caller_at(accounts(2), 3 * PRICE, 1_000);
+        contract.nft_mint_with_voucher(voucher, signature, None);
+    }
+}